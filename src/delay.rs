@@ -1,4 +1,7 @@
 //! Delay devices and providers
+use crate::asm;
+use crate::interrupt;
+use crate::peripheral::syst::{Half, Mode, TimerHalf};
 use crate::register::mcycle;
 use embedded_hal::blocking::delay::{DelayMs, DelayUs};
 
@@ -85,3 +88,170 @@ impl DelayMs<u8> for McycleDelay {
         self.delay_ms(ms as u32)
     }
 }
+
+/// Half of the SYST timer as a delay provider
+///
+/// Each delay runs the half in one-shot compare mode. Unlike `McycleDelay` it does not
+/// depend on `mcountinhibit`, and it can stall the hart with `wfi` while waiting.
+pub struct SystDelay<H> {
+    half: TimerHalf<H>,
+    ticks_second: u32,
+    wfi: bool,
+}
+
+impl<H> SystDelay<H>
+where
+    H: Half,
+{
+    /// Constructs the delay provider.
+    /// `ticks_second` should be the frequency the half counts at, in Hertz, after the prescaler
+    #[inline]
+    pub fn new(half: TimerHalf<H>, ticks_second: u32) -> Self {
+        Self {
+            half,
+            ticks_second,
+            wfi: false,
+        }
+    }
+
+    /// Sets whether to execute `wfi` while waiting instead of spinning
+    ///
+    /// The compare interrupt of the half is enabled while waiting. Its CLIC line has
+    /// to be unmasked, otherwise the hart is only woken up by other interrupts.
+    #[inline]
+    pub fn set_wfi(&mut self, wfi: bool) {
+        self.wfi = wfi;
+    }
+
+    /// Releases the timer half
+    #[inline]
+    pub fn free(mut self) -> TimerHalf<H> {
+        self.half.stop();
+        self.half
+    }
+
+    fn wait_ticks(&mut self, ticks: u32) {
+        let wfi = self.wfi;
+        self.half.stop();
+        self.half.reset();
+        self.half.set_compare(ticks);
+        self.half.modify_config(|config| {
+            config.set_mode(Mode::Cycle);
+            config.set_one_shot(true);
+            config.set_interrupt(wfi);
+        });
+        self.half.start();
+
+        // the half clears its enable bit once it reached the compare value
+        while self.half.get_config().enabled() {
+            if wfi {
+                // NOTE checking and stalling with interrupts disabled cannot miss the wake-up
+                interrupt::free(|| {
+                    if self.half.get_config().enabled() {
+                        unsafe { asm::wfi() };
+                    }
+                });
+            }
+        }
+    }
+}
+
+impl<H> DelayUs<u64> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline]
+    fn delay_us(&mut self, us: u64) {
+        // rounded up, so any non-zero delay waits at least one tick
+        let mut ticks = (us as u128 * self.ticks_second as u128 + 999_999) / 1_000_000;
+        while ticks > 0 {
+            let chunk = ticks.min(u32::MAX as u128);
+            self.wait_ticks(chunk as u32);
+            ticks -= chunk;
+        }
+    }
+}
+
+impl<H> DelayUs<u32> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_us(&mut self, us: u32) {
+        self.delay_us(us as u64)
+    }
+}
+
+// Implemented for constructions like `delay.delay_us(50_000);`
+impl<H> DelayUs<i32> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_us(&mut self, us: i32) {
+        assert!(us >= 0);
+        self.delay_us(us as u32);
+    }
+}
+
+impl<H> DelayUs<u16> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_us(&mut self, us: u16) {
+        self.delay_us(us as u32)
+    }
+}
+
+impl<H> DelayUs<u8> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_us(&mut self, us: u8) {
+        self.delay_us(us as u32)
+    }
+}
+
+impl<H> DelayMs<u32> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        self.delay_us((ms as u64) * 1000)
+    }
+}
+
+// Implemented for constructions like `delay.delay_ms(50_000);`
+impl<H> DelayMs<i32> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_ms(&mut self, ms: i32) {
+        assert!(ms >= 0);
+        self.delay_ms(ms as u32);
+    }
+}
+
+impl<H> DelayMs<u16> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_ms(ms as u32)
+    }
+}
+
+impl<H> DelayMs<u8> for SystDelay<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_ms(ms as u32)
+    }
+}
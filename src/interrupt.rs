@@ -62,10 +62,30 @@ where
     r
 }
 
+/// Trait for enums of external interrupt numbers.
+///
+/// This trait should be implemented by a peripheral access crate (PAC)
+/// on its enum of available external interrupts for a specific device.
+/// Each variant must convert to a `usize` of its interrupt number,
+/// which is its CLIC interrupt id.
+///
+/// # Safety
+///
+/// This trait must only be implemented on enums of device interrupts. Each
+/// enum variant must represent a distinct value (no duplicates are permitted),
+/// and must always return the same value (do not change at runtime).
+/// `from_number` must be the exact inverse of `number`.
+///
+/// These requirements ensure safe nesting of critical sections.
 pub unsafe trait InterruptNumber: Copy {
     /// Return the interrupt number associated with this variant.
     ///
     /// See trait documentation for safety requirements.
     fn number(self) -> usize;
+
+    /// Tries to convert an interrupt number into its variant.
+    ///
+    /// Returns `Err(n)` if `n` is not a valid interrupt number of this enum.
+    fn from_number(n: usize) -> Result<Self, usize>;
 }
 
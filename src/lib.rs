@@ -24,6 +24,40 @@
 //! and may cause functional problems in systems where some interrupts must be not be disabled
 //! or critical sections are managed as part of an RTOS. In these cases, you should use
 //! a target-specific implementation instead, typically provided by a HAL or RTOS crate.
+//!
+//! ## `clic-async`
+//!
+//! This feature provides futures to wait on CLIC interrupt lines from an async executor,
+//! without an allocator, see `peripheral::clic::asynch`.
+//!
+//! ## `clic-latency`
+//!
+//! This feature records per CLIC line how often it was taken and the `mcycle` latency between
+//! raising the interrupt and entering its handler, see `peripheral::clic::latency`.
+//!
+//! ## `clic-storm-guard`
+//!
+//! This feature masks CLIC lines taken too often within a window of `mcycle` cycles,
+//! see `peripheral::clic::storm`.
+//!
+//! The per-line tables of `clic-async`, `clic-latency` and `clic-storm-guard` are `static`s
+//! sized by the application with a const generic line count `N`. Interrupt numbers of `N` or
+//! more are not tracked: waiting on them panics, while latency and storm statistics ignore them.
+//!
+//! ## `clic-swi`
+//!
+//! This feature provides lock-free deferred work queues run by spare CLIC lines, see
+//! `peripheral::clic::swi`. It requires atomic compare-and-swap instructions.
+//!
+//! ## `syst-embassy`
+//!
+//! This feature provides an `embassy-time` driver on the cascaded SYST timer,
+//! see `peripheral::syst::embassy`.
+//!
+//! ## `syst-monotonic`
+//!
+//! This feature implements the RTIC `Monotonic` trait on the cascaded SYST timer,
+//! with time expressed as `fugit` instants, see `peripheral::syst::monotonic`.
 
 #![no_std]
 
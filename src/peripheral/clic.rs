@@ -1,11 +1,28 @@
+use core::marker::PhantomData;
+
 #[cfg(not(armv6m))]
 use volatile_register::RO;
 use volatile_register::RW;
 
 use crate::interrupt::InterruptNumber;
-use crate::peripheral::CLIC;
+use crate::peripheral::{ClicLayout, HartCLIC, SupervisorCLIC, UserCLIC, CLIC};
 use crate::register::mcause;
 
+#[cfg(feature = "clic-async")]
+pub mod asynch;
+pub mod config;
+pub mod shared;
+#[cfg(feature = "clic-storm-guard")]
+pub mod storm;
+#[cfg(feature = "clic-latency")]
+pub mod latency;
+#[cfg(feature = "clic")]
+pub mod srp;
+#[cfg(feature = "clic-swi")]
+pub mod swi;
+#[cfg(feature = "clic")]
+pub mod vectors;
+
 /// Writes the `bits` into `base_values` at pos [low_bit, high_bit] both included
 #[inline]
 unsafe fn write_bits(base_value: u32, high_bit: u8, low_bit: u8, bits: u32) -> u32 {
@@ -19,7 +36,7 @@ unsafe fn write_bits(base_value: u32, high_bit: u8, low_bit: u8, bits: u32) -> u
 
 /// Reads the bits from `base_values` at pos [low_bit, high_bit] both included
 #[inline]
-fn read_bits(base_value: u32, high_bit: u8, low_bit: u8) -> u32 {
+pub(crate) fn read_bits(base_value: u32, high_bit: u8, low_bit: u8) -> u32 {
     let mut mask = 0;
     for i in low_bit..high_bit + 1 {
         mask += 1 << i;
@@ -37,17 +54,136 @@ pub struct InterruptBlock {
     pub ctl: RW<u32>,
 }
 
+impl InterruptBlock {
+    /// Disables the interrupt
+    #[inline]
+    pub fn mask(&self) {
+        unsafe { self.ie.write(0) }
+    }
+
+    /// Enables the interrupt
+    #[inline]
+    pub fn unmask(&self) {
+        unsafe { self.ie.write(1) }
+    }
+
+    /// Checks if the interrupt is enabled
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.ie.read() == 1
+    }
+
+    /// Checks if the interrupt is pending
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        self.ip.read() == 1
+    }
+
+    /// Forces the interrupt into pending state
+    #[inline]
+    pub fn pend(&self) {
+        unsafe { self.ip.write(1) }
+    }
+
+    /// Clears the pending state of the interrupt
+    #[inline]
+    pub fn unpend(&self) {
+        unsafe { self.ip.write(0) }
+    }
+
+    /// Returns the raw `clicintctl` value of the interrupt
+    #[inline]
+    pub fn priority(&self) -> u8 {
+        self.ctl.read() as u8
+    }
+
+    /// Writes the raw `clicintctl` value of the interrupt
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_priority(&self, ctl: u8) {
+        self.ctl.write(ctl.into())
+    }
+
+    /// Enables or disables selective hardware vectoring of the interrupt
+    ///
+    /// # Safety
+    ///
+    /// The interrupt has to be dispatched by its vector table entry when enabled,
+    /// and by the common trap handler otherwise.
+    #[inline]
+    pub unsafe fn set_shv(&self, shv: bool) {
+        let before = self.attr.read();
+        self.attr.write(write_bits(before, 0, 0, shv as u32))
+    }
+
+    /// Sets the trigger of the interrupt
+    ///
+    /// # Safety
+    ///
+    /// The handler of the interrupt has to clear its pending state as `trig` requires.
+    #[inline]
+    pub unsafe fn set_trig(&self, trig: Trigger) {
+        let before = self.attr.read();
+        self.attr.write(write_bits(before, 2, 1, trig as u32))
+    }
+}
+
 /// Register block
 #[repr(C)]
 pub struct RegisterBlock {
     pub cliccfg: RW<u32>,
     pub clicinfo: RO<u32>,
     pub clicxnxticonf: RW<u32>,
-    reserved: [u8; 0x1000 - 4 * 3],
+    reserved0: [u8; 0x40 - 4 * 3],
+    pub clicinttrig: [RW<u32>; 32],
+    reserved1: [u8; 0x1000 - 0x40 - 4 * 32],
+    pub intcfg: [InterruptBlock; 4096],
+}
+
+/// Register block of the supervisor and user mode windows
+///
+/// These windows only expose the interrupts assigned to their privilege mode,
+/// the CLIC configuration registers are not accessible.
+#[repr(C)]
+pub struct ModeRegisterBlock {
+    reserved: [u8; 0x1000],
     pub intcfg: [InterruptBlock; 4096],
 }
 
+/// Iterator over the pending interrupts, see [`CLIC::pending`]
+pub struct Pending<I> {
+    next: usize,
+    end: usize,
+    _marker: PhantomData<I>,
+}
+
+impl<I> Iterator for Pending<I>
+where
+    I: InterruptNumber,
+{
+    type Item = I;
+
+    #[inline]
+    fn next(&mut self) -> Option<I> {
+        while self.next < self.end {
+            let nr = self.next;
+            self.next += 1;
+            // NOTE(unsafe) atomic read with no side effects
+            if unsafe { (*CLIC::PTR).intcfg[nr].ip.read() } == 1 {
+                if let Ok(interrupt) = I::from_number(nr) {
+                    return Some(interrupt);
+                }
+            }
+        }
+        None
+    }
+}
+
 /// Trigger enum
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Trigger {
     LevelPositive = 0,
     EdgePositive = 1,
@@ -55,6 +191,131 @@ pub enum Trigger {
     EdgeNegative = 3,
 }
 
+impl Trigger {
+    /// All trigger types, indexed by their encoding
+    pub const ALL: [Trigger; 4] = [
+        Trigger::LevelPositive,
+        Trigger::EdgePositive,
+        Trigger::LevelNegative,
+        Trigger::EdgeNegative,
+    ];
+}
+
+/// `clicinfo` register
+#[derive(Clone, Copy, Debug)]
+pub struct ClicInfo {
+    bits: u32,
+}
+
+impl ClicInfo {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Returns the number of interrupt inputs
+    #[inline]
+    pub fn num_interrupt(&self) -> u16 {
+        read_bits(self.bits, 12, 0) as u16
+    }
+
+    /// Returns the architecture version
+    #[inline]
+    pub fn arch_version(&self) -> u8 {
+        read_bits(self.bits, 20, 17) as u8
+    }
+
+    /// Returns the implementation version
+    #[inline]
+    pub fn impl_version(&self) -> u8 {
+        read_bits(self.bits, 16, 13) as u8
+    }
+
+    /// Returns how many bits are implemented in the `clicintctl` registers (`CLICINTCTLBITS`)
+    #[inline]
+    pub fn clicintctlbits(&self) -> u8 {
+        read_bits(self.bits, 24, 21) as u8
+    }
+
+    /// Returns the number of interrupt triggers
+    #[inline]
+    pub fn num_trigger(&self) -> u8 {
+        read_bits(self.bits, 30, 25) as u8
+    }
+}
+
+/// `cliccfg` register
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClicCfg {
+    bits: u32,
+}
+
+impl ClicCfg {
+    /// Creates a configuration from its fields
+    #[inline]
+    pub const fn new(nvbits: bool, nlbits: u8, nmbits: u8) -> ClicCfg {
+        ClicCfg {
+            bits: (nvbits as u32) | ((nlbits as u32 & 0xF) << 1) | ((nmbits as u32 & 0x3) << 5),
+        }
+    }
+
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Is selective hardware vectoring implemented (`nvbits`)
+    #[inline]
+    pub fn nvbits(&self) -> bool {
+        read_bits(self.bits, 0, 0) == 1
+    }
+
+    /// Returns the number of bits used for the interrupt level (`nlbits`)
+    #[inline]
+    pub fn nlbits(&self) -> u8 {
+        read_bits(self.bits, 4, 1) as u8
+    }
+
+    /// Returns the number of bits used for the privilege mode (`nmbits`)
+    #[inline]
+    pub fn nmbits(&self) -> u8 {
+        read_bits(self.bits, 6, 5) as u8
+    }
+}
+
+/// Settings supported by the hardware, see [`CLIC::probe`]
+#[derive(Clone, Copy, Debug)]
+pub struct Capabilities {
+    /// Bits of `clicintctl` that can be written
+    pub ctl_mask: u8,
+    /// Supported trigger types, indexed by their encoding
+    pub triggers: [bool; 4],
+    /// Selective hardware vectoring can be enabled
+    pub shv: bool,
+    /// Largest `nlbits` value the hardware stores
+    pub max_nlbits: u8,
+    /// Largest `nmbits` value the hardware stores
+    pub max_nmbits: u8,
+}
+
+impl Capabilities {
+    /// Is `trig` supported
+    #[inline]
+    pub fn supports(&self, trig: Trigger) -> bool {
+        self.triggers[trig as usize]
+    }
+}
+
+/// Privilege mode an interrupt is taken in
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    User = 0,
+    Supervisor = 1,
+    Machine = 3,
+}
+
 impl CLIC {
     //* IE
     /// Disables `interrupt`
@@ -64,7 +325,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ie.write(0) }
+        unsafe { (*Self::PTR).intcfg[nr].mask() }
     }
 
     /// Enables `interrupt`
@@ -76,7 +337,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ie.write(1) }
+        unsafe { (*Self::PTR).intcfg[nr].unmask() }
     }
 
     /// Checks if `interrupt` is enabled
@@ -86,7 +347,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ie.read() == 1 }
+        unsafe { (*Self::PTR).intcfg[nr].is_enabled() }
     }
 
     /// Checks if `interrupt` is pending
@@ -96,7 +357,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ip.read() == 1 }
+        unsafe { (*Self::PTR).intcfg[nr].is_pending() }
     }
 
     //* CTL
@@ -108,7 +369,7 @@ impl CLIC {
     {
         let nr = interrupt.number();
         // NOTE(unsafe) atomic read with no side effects
-        unsafe { (*Self::PTR).intcfg[nr].ctl.read() as u8 }
+        unsafe { (*Self::PTR).intcfg[nr].priority() }
     }
 
     /// Sets the "priority" of `interrupt` to `prio`
@@ -118,7 +379,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        (*Self::PTR).intcfg[nr].ctl.write(prio.into())
+        (*Self::PTR).intcfg[nr].set_priority(prio)
     }
 
     /// Is `interrupt` active or pre-empted and stacked
@@ -137,15 +398,40 @@ impl CLIC {
         (mcause::read().bits() & mask) == nr
     }
 
+    /// Returns the interrupt that caused the current trap, if it is a valid `I`
+    #[inline]
+    pub fn active<I>() -> Option<I>
+    where
+        I: InterruptNumber,
+    {
+        mcause::read().interrupt_as().and_then(Result::ok)
+    }
+
     //* IP
+    /// Returns an iterator over all pending interrupts that are valid `I`
+    #[inline]
+    pub fn pending<I>() -> Pending<I>
+    where
+        I: InterruptNumber,
+    {
+        Pending {
+            next: 0,
+            end: Self::get_info().num_interrupt() as usize,
+            _marker: PhantomData,
+        }
+    }
+
     /// Forces `interrupt` into pending state
     #[inline]
     pub fn pend<I>(interrupt: I)
     where
         I: InterruptNumber,
     {
+        #[cfg(feature = "clic-latency")]
+        latency::pended(interrupt);
+
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ip.write(1) }
+        unsafe { (*Self::PTR).intcfg[nr].pend() }
     }
 
     /// Clears `interrupt`'s pending state
@@ -155,7 +441,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        unsafe { (*Self::PTR).intcfg[nr].ip.write(0) }
+        unsafe { (*Self::PTR).intcfg[nr].unpend() }
     }
 
     //* ATTR
@@ -166,10 +452,7 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        let before = (*Self::PTR).intcfg[nr].attr.read();
-        let edited = write_bits(before, 0, 0, 1);
-
-        (*Self::PTR).intcfg[nr].attr.write(edited)
+        (*Self::PTR).intcfg[nr].set_shv(true)
     }
 
     /// Disables "selective hardware vectoring" of `interrupt`
@@ -179,25 +462,84 @@ impl CLIC {
         I: InterruptNumber,
     {
         let nr = interrupt.number();
-        let before = (*Self::PTR).intcfg[nr].attr.read();
-        let edited = write_bits(before, 0, 0, 0);
-
-        (*Self::PTR).intcfg[nr].attr.write(edited)
+        (*Self::PTR).intcfg[nr].set_shv(false)
     }
 
     /// Sets "trigger" of `interrupt`
     #[inline]
     pub unsafe fn set_trig<I>(&mut self, interrupt: I, trig: Trigger)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        (*Self::PTR).intcfg[nr].set_trig(trig)
+    }
+
+    /// Configures `interrupt` as rising edge triggered with the raw `clicintctl` value `ctl` and unmasks it
+    ///
+    /// The edge trigger latches every pend, from software or from a peripheral pulse,
+    /// until the line is taken.
+    ///
+    /// # Safety
+    ///
+    /// Unmasking the line can break mask-based critical sections, and the handler of
+    /// `interrupt` has to be ready to run.
+    #[inline]
+    pub unsafe fn enable_edge<I>(&mut self, interrupt: I, ctl: u8)
+    where
+        I: InterruptNumber,
+    {
+        self.set_trig(interrupt, Trigger::EdgePositive);
+        self.set_priority(interrupt, ctl);
+        Self::unmask(interrupt);
+    }
+
+    /// Returns the "trigger" of `interrupt`
+    #[inline]
+    pub fn get_trig<I>(interrupt: I) -> Trigger
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        // NOTE(unsafe) atomic read with no side effects
+        let attr = unsafe { (*Self::PTR).intcfg[nr].attr.read() };
+        Trigger::ALL[read_bits(attr, 2, 1) as usize]
+    }
+
+    /// Sets the privilege mode `interrupt` is taken in
+    ///
+    /// Depending on the configured number of mode bits, the hardware may ignore
+    /// this setting or store a different mode.
+    ///
+    /// # Safety
+    ///
+    /// A handler for `interrupt` has to be installed in the trap vector of `mode`.
+    #[inline]
+    pub unsafe fn set_mode<I>(&mut self, interrupt: I, mode: Mode)
     where
         I: InterruptNumber,
     {
         let nr = interrupt.number();
         let before = (*Self::PTR).intcfg[nr].attr.read();
-        let edited = write_bits(before, 2, 1, trig as u32);
+        let edited = write_bits(before, 7, 6, mode as u32);
         (*Self::PTR).intcfg[nr].attr.write(edited)
     }
 
-    // TODO: implement attr mode bits if needed
+    /// Returns the privilege mode `interrupt` is taken in
+    #[inline]
+    pub fn get_mode<I>(interrupt: I) -> Mode
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        // NOTE(unsafe) atomic read with no side effects
+        let attr = unsafe { (*Self::PTR).intcfg[nr].attr.read() };
+        match read_bits(attr, 7, 6) {
+            0 => Mode::User,
+            1 => Mode::Supervisor,
+            _ => Mode::Machine,
+        }
+    }
 
     //* CLIC CFG
 
@@ -230,14 +572,35 @@ impl CLIC {
     // Gets flag if vectored interrupt handling is implemented in hardware
     pub unsafe fn has_interrupt_vectoring(&mut self) -> bool {
         let before = (*Self::PTR).cliccfg.read();
-        read_bits(before, 0, 0) != 1
+        read_bits(before, 0, 0) == 1
+    }
+
+    /// Reads the `cliccfg` register
+    #[inline]
+    pub fn get_cfg() -> ClicCfg {
+        // NOTE(unsafe) atomic read with no side effects
+        ClicCfg {
+            bits: unsafe { (*Self::PTR).cliccfg.read() },
+        }
+    }
+
+    /// Writes the `cliccfg` register
+    ///
+    /// # Safety
+    ///
+    /// Changing the split of `clicintctl` into level and priority bits changes the
+    /// level of every interrupt, which can break level-based critical sections.
+    #[inline]
+    pub unsafe fn set_cfg(&mut self, cfg: ClicCfg) {
+        (*Self::PTR).cliccfg.write(cfg.bits)
     }
 
     //* CLIC INFO
     // Gets actual number of maximum interrupt inputs supported in this implementation
+    #[deprecated(note = "use `CLIC::get_info().num_interrupt()` instead")]
     pub unsafe fn get_num_int(&mut self) -> u32 {
         let before = (*Self::PTR).clicinfo.read();
-        read_bits(before, 30, 25)
+        read_bits(before, 12, 0)
     }
 
     // Gets how many hardware bits are actually implemented in the clicintctl registers
@@ -258,6 +621,128 @@ impl CLIC {
         read_bits(before, 12, 0)
     }
 
+    /// Reads the `clicinfo` register
+    #[inline]
+    pub fn get_info() -> ClicInfo {
+        // NOTE(unsafe) atomic read with no side effects
+        ClicInfo {
+            bits: unsafe { (*Self::PTR).clicinfo.read() },
+        }
+    }
+
+    /// Finds out which settings the hardware implements for `interrupt`
+    ///
+    /// Each setting is written and read back, then the previous values are restored.
+    ///
+    /// # Safety
+    ///
+    /// `interrupt` must be masked and interrupts must not be serviced while probing.
+    pub unsafe fn probe<I>(&mut self, interrupt: I) -> Capabilities
+    where
+        I: InterruptNumber,
+    {
+        let block = &(*Self::PTR).intcfg[interrupt.number()];
+        let attr = block.attr.read();
+        let ctl = block.ctl.read();
+        let cfg = (*Self::PTR).cliccfg.read();
+
+        block.ctl.write(0xFF);
+        let ctl_mask = block.ctl.read() as u8;
+        block.ctl.write(ctl);
+
+        let mut triggers = [false; 4];
+        for trig in Trigger::ALL.iter() {
+            block.attr.write(write_bits(attr, 2, 1, *trig as u32));
+            triggers[*trig as usize] = read_bits(block.attr.read(), 2, 1) == *trig as u32;
+        }
+
+        block.attr.write(write_bits(attr, 0, 0, 1));
+        let shv = read_bits(block.attr.read(), 0, 0) == 1;
+        block.attr.write(attr);
+
+        (*Self::PTR).cliccfg.write(write_bits(cfg, 6, 1, 0b11_1111));
+        let probed = (*Self::PTR).cliccfg.read();
+        (*Self::PTR).cliccfg.write(cfg);
+
+        Capabilities {
+            ctl_mask,
+            triggers,
+            shv,
+            max_nlbits: read_bits(probed, 4, 1) as u8,
+            max_nmbits: read_bits(probed, 6, 5) as u8,
+        }
+    }
+
+    //* CLIC INTTRIG
+    /// Asserts that `trigger` is implemented
+    #[inline]
+    fn check_trigger(trigger: usize) {
+        let implemented = (Self::get_info().num_trigger() as usize).min(32);
+        assert!(trigger < implemented, "trigger not implemented");
+    }
+
+    /// Arms `trigger` to fire a debug trigger when `interrupt` is taken
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trigger` is not smaller than `clicinfo.num_trigger`.
+    #[inline]
+    pub fn arm_trigger<I>(&mut self, trigger: usize, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        Self::check_trigger(trigger);
+        let bits = (1 << 31) | (interrupt.number() as u32 & 0x1FFF);
+        unsafe { (*Self::PTR).clicinttrig[trigger].write(bits) }
+    }
+
+    /// Disarms `trigger` and clears its interrupt number
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trigger` is not smaller than `clicinfo.num_trigger`.
+    #[inline]
+    pub fn clear_trigger(&mut self, trigger: usize) {
+        Self::check_trigger(trigger);
+        unsafe { (*Self::PTR).clicinttrig[trigger].write(0) }
+    }
+
+    /// Checks if `trigger` is armed
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trigger` is not smaller than `clicinfo.num_trigger`.
+    #[inline]
+    pub fn is_trigger_armed(trigger: usize) -> bool {
+        Self::check_trigger(trigger);
+        // NOTE(unsafe) atomic read with no side effects
+        let bits = unsafe { (*Self::PTR).clicinttrig[trigger].read() };
+        read_bits(bits, 31, 31) == 1
+    }
+
+    /// Returns the interrupt `trigger` is armed on
+    ///
+    /// Returns `None` if `trigger` is not armed and `Some(Err(nr))` if `nr`
+    /// is not a valid interrupt number of `I`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trigger` is not smaller than `clicinfo.num_trigger`.
+    #[inline]
+    pub fn get_trigger<I>(trigger: usize) -> Option<Result<I, usize>>
+    where
+        I: InterruptNumber,
+    {
+        Self::check_trigger(trigger);
+        // NOTE(unsafe) atomic read with no side effects
+        let bits = unsafe { (*Self::PTR).clicinttrig[trigger].read() };
+        if read_bits(bits, 31, 31) == 1 {
+            Some(I::from_number(read_bits(bits, 12, 0) as usize))
+        } else {
+            None
+        }
+    }
+
     //* CLIC NXTI
     // Enables NXTI
     pub unsafe fn enable_nxti(&mut self) {
@@ -275,3 +760,274 @@ impl CLIC {
     }
 
 }
+
+impl<L> HartCLIC<L>
+where
+    L: ClicLayout,
+{
+    /// Disables `interrupt`
+    #[inline]
+    pub fn mask<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].mask()
+    }
+
+    /// Enables `interrupt`
+    #[inline]
+    pub fn unmask<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].unmask()
+    }
+
+    /// Checks if `interrupt` is enabled
+    #[inline]
+    pub fn is_enabled<I>(&self, interrupt: I) -> bool
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].is_enabled()
+    }
+
+    /// Checks if `interrupt` is pending
+    #[inline]
+    pub fn is_pending<I>(&self, interrupt: I) -> bool
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].is_pending()
+    }
+
+    /// Forces `interrupt` into pending state
+    #[inline]
+    pub fn pend<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].pend()
+    }
+
+    /// Clears `interrupt`'s pending state
+    #[inline]
+    pub fn unpend<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].unpend()
+    }
+
+    /// Returns the CLIC priority of `interrupt`
+    #[inline]
+    pub fn get_priority<I>(&self, interrupt: I) -> u8
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].priority()
+    }
+
+    /// Sets the "priority" of `interrupt` to `prio`
+    ///
+    /// # Safety
+    ///
+    /// Changing priority levels can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_priority<I>(&mut self, interrupt: I, prio: u8)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].set_priority(prio)
+    }
+
+    /// Enables "selective hardware vectoring" of `interrupt`
+    ///
+    /// # Safety
+    ///
+    /// The vector table entry of `interrupt` has to point to its handler.
+    #[inline]
+    pub unsafe fn enable_shv<I>(&mut self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].set_shv(true)
+    }
+
+    /// Disables "selective hardware vectoring" of `interrupt`
+    ///
+    /// # Safety
+    ///
+    /// The common trap handler has to dispatch `interrupt`.
+    #[inline]
+    pub unsafe fn disable_shv<I>(&mut self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].set_shv(false)
+    }
+
+    /// Sets "trigger" of `interrupt`
+    ///
+    /// # Safety
+    ///
+    /// The handler of `interrupt` has to clear its pending state as `trig` requires.
+    #[inline]
+    pub unsafe fn set_trig<I>(&mut self, interrupt: I, trig: Trigger)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        self.intcfg[nr].set_trig(trig)
+    }
+}
+
+/// Implements the operations allowed in a less privileged CLIC window
+macro_rules! mode_window {
+    ($window:ident) => {
+        impl<L> $window<L>
+        where
+            L: ClicLayout,
+        {
+            /// Disables `interrupt`
+            #[inline]
+            pub fn mask<I>(interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].mask() }
+            }
+
+            /// Enables `interrupt`
+            #[inline]
+            pub fn unmask<I>(interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].unmask() }
+            }
+
+            /// Checks if `interrupt` is enabled
+            #[inline]
+            pub fn is_enabled<I>(interrupt: I) -> bool
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].is_enabled() }
+            }
+
+            /// Checks if `interrupt` is pending
+            #[inline]
+            pub fn is_pending<I>(interrupt: I) -> bool
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].is_pending() }
+            }
+
+            /// Forces `interrupt` into pending state
+            #[inline]
+            pub fn pend<I>(interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].pend() }
+            }
+
+            /// Clears `interrupt`'s pending state
+            #[inline]
+            pub fn unpend<I>(interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                unsafe { (*Self::PTR).intcfg[nr].unpend() }
+            }
+
+            /// Returns the CLIC priority of `interrupt`
+            #[inline]
+            pub fn get_priority<I>(interrupt: I) -> u8
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                // NOTE(unsafe) atomic read with no side effects
+                unsafe { (*Self::PTR).intcfg[nr].priority() }
+            }
+
+            /// Sets the "priority" of `interrupt` to `prio`
+            ///
+            /// # Safety
+            ///
+            /// Changing priority levels can break priority-based critical sections.
+            #[inline]
+            pub unsafe fn set_priority<I>(&mut self, interrupt: I, prio: u8)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                (*Self::PTR).intcfg[nr].set_priority(prio)
+            }
+
+            /// Enables "selective hardware vectoring" of `interrupt`
+            ///
+            /// # Safety
+            ///
+            /// The vector table entry of `interrupt` has to point to its handler.
+            #[inline]
+            pub unsafe fn enable_shv<I>(&mut self, interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                (*Self::PTR).intcfg[nr].set_shv(true)
+            }
+
+            /// Disables "selective hardware vectoring" of `interrupt`
+            ///
+            /// # Safety
+            ///
+            /// The common trap handler has to dispatch `interrupt`.
+            #[inline]
+            pub unsafe fn disable_shv<I>(&mut self, interrupt: I)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                (*Self::PTR).intcfg[nr].set_shv(false)
+            }
+
+            /// Sets "trigger" of `interrupt`
+            ///
+            /// # Safety
+            ///
+            /// The handler of `interrupt` has to clear its pending state as `trig` requires.
+            #[inline]
+            pub unsafe fn set_trig<I>(&mut self, interrupt: I, trig: Trigger)
+            where
+                I: InterruptNumber,
+            {
+                let nr = interrupt.number();
+                (*Self::PTR).intcfg[nr].set_trig(trig)
+            }
+        }
+    };
+}
+
+mode_window!(SupervisorCLIC);
+mode_window!(UserCLIC);
@@ -0,0 +1,181 @@
+//! Async waiting on CLIC interrupt lines
+//!
+//! [`Waiters::wait`] returns a future that unmasks the line when polled and resolves
+//! once the line is taken. The handler of the line has to call [`Waiters::on_interrupt`],
+//! which masks the line again and wakes the waiting task. A line keeps a single
+//! waker, so it is meant to be awaited by one task at a time.
+//!
+//! ``` ignore
+//! static WAITERS: Waiters<32> = Waiters::new();
+//!
+//! async fn transfer_done() {
+//!     WAITERS.wait(Interrupt::DMA0).await;
+//! }
+//!
+//! fn dma0_handler() {
+//!     WAITERS.on_interrupt(Interrupt::DMA0);
+//! }
+//! ```
+
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use critical_section::Mutex;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::CLIC;
+
+struct Slot {
+    waker: Option<Waker>,
+    fired: bool,
+}
+
+impl Slot {
+    const EMPTY: Slot = Slot {
+        waker: None,
+        fired: false,
+    };
+}
+
+/// One waker slot for each of `N` CLIC lines
+pub struct Waiters<const N: usize> {
+    slots: Mutex<RefCell<[Slot; N]>>,
+}
+
+impl<const N: usize> Waiters<N> {
+    /// Creates the slots with no waiting task
+    #[inline]
+    pub const fn new() -> Self {
+        Waiters {
+            slots: Mutex::new(RefCell::new([Slot::EMPTY; N])),
+        }
+    }
+
+    /// Wakes the task waiting on `interrupt`, to be called from its handler
+    ///
+    /// The line is masked and its pending state cleared, so the handler does not run
+    /// again before the task polls its future.
+    #[inline]
+    pub fn on_interrupt<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        CLIC::mask(interrupt);
+        CLIC::unpend(interrupt);
+
+        let nr = interrupt.number();
+        if nr >= N {
+            return;
+        }
+        let waker = critical_section::with(|cs| {
+            let slot = &mut self.slots.borrow(cs).borrow_mut()[nr];
+            slot.fired = true;
+            slot.waker.take()
+        });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Returns a future that resolves when `interrupt` is taken
+    ///
+    /// # Panics
+    ///
+    /// The future panics when polled if the number of `interrupt` is not smaller than `N`.
+    #[inline]
+    pub fn wait<I>(&self, interrupt: I) -> Wait<'_, I, N>
+    where
+        I: InterruptNumber,
+    {
+        Wait {
+            waiters: self,
+            interrupt,
+            waker: None,
+        }
+    }
+}
+
+impl<const N: usize> Default for Waiters<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future returned by [`Waiters::wait`]
+///
+/// Dropping the future before it resolves masks the line again, unless another
+/// task registered itself on the line since.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Wait<'a, I, const N: usize>
+where
+    I: InterruptNumber,
+{
+    waiters: &'a Waiters<N>,
+    interrupt: I,
+    waker: Option<Waker>,
+}
+
+// NOTE the future is never structurally pinned
+impl<I, const N: usize> Unpin for Wait<'_, I, N> where I: InterruptNumber {}
+
+impl<I, const N: usize> Future for Wait<'_, I, N>
+where
+    I: InterruptNumber,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let nr = this.interrupt.number();
+        assert!(nr < N);
+
+        let armed = this.waker.is_some();
+        let fired = critical_section::with(|cs| {
+            let slot = &mut this.waiters.slots.borrow(cs).borrow_mut()[nr];
+            if armed && slot.fired {
+                slot.fired = false;
+                true
+            } else {
+                slot.fired = false;
+                slot.waker = Some(cx.waker().clone());
+                false
+            }
+        });
+
+        if fired {
+            this.waker = None;
+            Poll::Ready(())
+        } else {
+            this.waker = Some(cx.waker().clone());
+            CLIC::unmask(this.interrupt);
+            Poll::Pending
+        }
+    }
+}
+
+impl<I, const N: usize> Drop for Wait<'_, I, N>
+where
+    I: InterruptNumber,
+{
+    fn drop(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            let nr = self.interrupt.number();
+            let ours = critical_section::with(|cs| {
+                let slot = &mut self.waiters.slots.borrow(cs).borrow_mut()[nr];
+                match &slot.waker {
+                    Some(registered) if registered.will_wake(&waker) => {
+                        slot.waker = None;
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            if ours {
+                CLIC::mask(self.interrupt);
+            }
+        }
+    }
+}
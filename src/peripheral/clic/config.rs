@@ -0,0 +1,151 @@
+//! Declarative CLIC configuration
+//!
+//! The whole configuration is described by a `const` [`Config`] table, written in one
+//! go by [`Config::apply`], which reads every register back and reports the fields
+//! the hardware stored differently.
+//!
+//! ``` ignore
+//! const CONFIG: Config<Interrupt> = Config {
+//!     cfg: ClicCfg::new(true, 3, 0),
+//!     lines: &[LineConfig {
+//!         interrupt: Interrupt::UART,
+//!         trigger: Trigger::LevelPositive,
+//!         mode: Mode::Machine,
+//!         level: 2,
+//!         priority: 0,
+//!         shv: true,
+//!         enabled: true,
+//!     }],
+//! };
+//!
+//! let mismatches = unsafe { CONFIG.apply(&mut p.CLIC, |m| log(m)) };
+//! ```
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::clic::{read_bits, ClicCfg, Mode, Trigger};
+use crate::peripheral::CLIC;
+
+/// Configuration of a single CLIC line
+#[derive(Clone, Copy, Debug)]
+pub struct LineConfig<I> {
+    /// Line to configure
+    pub interrupt: I,
+    /// Trigger type
+    pub trigger: Trigger,
+    /// Privilege mode the interrupt is taken in
+    pub mode: Mode,
+    /// Interrupt level, stored in the upper `nlbits` bits of `clicintctl`
+    pub level: u8,
+    /// Priority, stored in the `clicintctl` bits below the level
+    pub priority: u8,
+    /// Selective hardware vectoring
+    pub shv: bool,
+    /// Enable the line after configuring it
+    pub enabled: bool,
+}
+
+/// Configuration of the CLIC
+#[derive(Clone, Copy, Debug)]
+pub struct Config<I: 'static> {
+    /// `cliccfg` register
+    pub cfg: ClicCfg,
+    /// Configuration of the lines
+    pub lines: &'static [LineConfig<I>],
+}
+
+/// Field of the configuration
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    Cfg,
+    Trigger,
+    Mode,
+    Level,
+    Priority,
+    Shv,
+    Enabled,
+}
+
+/// Field the hardware stored with a different value than written
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mismatch<I> {
+    /// Line of the field, `None` for `cliccfg`
+    pub interrupt: Option<I>,
+    pub field: Field,
+    /// Value written
+    pub written: u32,
+    /// Value read back
+    pub read: u32,
+}
+
+impl<I> Config<I>
+where
+    I: InterruptNumber,
+{
+    /// Writes the configuration and reads it back
+    ///
+    /// `report` is called for every field the hardware stored differently, including
+    /// levels and priorities too wide for their field. Priority bits not implemented
+    /// in `clicintctl` are not compared. Each line is masked
+    /// while it is configured. Returns the number of mismatches.
+    ///
+    /// # Safety
+    ///
+    /// Changing levels can break mask-based critical sections.
+    pub unsafe fn apply<F>(&self, clic: &mut CLIC, mut report: F) -> usize
+    where
+        F: FnMut(Mismatch<I>),
+    {
+        let mut mismatches = 0;
+        let mut check = |interrupt, field, written: u32, read: u32| {
+            if written != read {
+                mismatches += 1;
+                report(Mismatch {
+                    interrupt,
+                    field,
+                    written,
+                    read,
+                });
+            }
+        };
+
+        clic.set_cfg(self.cfg);
+        let cfg = CLIC::get_cfg();
+        check(None, Field::Cfg, self.cfg.bits(), cfg.bits() & 0x7F);
+
+        let nlbits = cfg.nlbits().min(8) as u32;
+        let ctlbits = CLIC::get_info().clicintctlbits().min(8) as u32;
+        let implemented = (0xFF00u32 >> ctlbits) & 0xFF;
+
+        for line in self.lines {
+            let interrupt = line.interrupt;
+            let block = &(*CLIC::PTR).intcfg[interrupt.number()];
+
+            CLIC::mask(interrupt);
+            let attr = (line.shv as u32) | ((line.trigger as u32) << 1) | ((line.mode as u32) << 6);
+            block.attr.write(attr);
+            let ctl = (((line.level as u32) << (8 - nlbits)) & 0xFF)
+                | (line.priority as u32 & (0xFF >> nlbits));
+            block.ctl.write(ctl);
+            if line.enabled {
+                CLIC::unmask(interrupt);
+            }
+
+            let attr = block.attr.read();
+            let read_ctl = block.ctl.read();
+            let level = |ctl: u32| if nlbits == 0 { 0 } else { (ctl & 0xFF) >> (8 - nlbits) };
+            let priority = |ctl: u32| ctl & (0xFF >> nlbits) & implemented;
+            let irq = Some(interrupt);
+
+            check(irq, Field::Trigger, line.trigger as u32, read_bits(attr, 2, 1));
+            check(irq, Field::Mode, line.mode as u32, read_bits(attr, 7, 6));
+            check(irq, Field::Shv, line.shv as u32, read_bits(attr, 0, 0));
+            // NOTE compared against the table, values that do not fit their field are reported
+            let unimplemented = (0xFF >> nlbits) & !implemented;
+            check(irq, Field::Level, line.level as u32, level(read_ctl));
+            check(irq, Field::Priority, line.priority as u32 & !unimplemented, priority(read_ctl));
+            check(irq, Field::Enabled, line.enabled as u32, block.ie.read());
+        }
+
+        mismatches
+    }
+}
@@ -0,0 +1,197 @@
+//! Interrupt latency instrumentation
+//!
+//! A [`LatencyTable`] records, for each CLIC line, how many times its handler was
+//! entered and how many `mcycle` cycles passed between raising the interrupt and
+//! entering the handler. The statistics can be read while the system runs, e.g. to
+//! report worst-case latencies measured during a test campaign.
+//!
+//! The raise timestamp is captured by [`CLIC::pend`](crate::peripheral::CLIC::pend)
+//! once the table is installed, or set with [`LatencyTable::mark`] for interrupts
+//! raised by hardware, e.g. from the compare value of a timer. Handlers call
+//! [`LatencyTable::record`] on entry.
+//!
+//! ``` ignore
+//! static LATENCY: LatencyTable<32> = LatencyTable::new();
+//!
+//! LATENCY.install();
+//!
+//! fn dma0_handler() {
+//!     LATENCY.record(Interrupt::DMA0);
+//! }
+//! ```
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+
+use crate::interrupt::InterruptNumber;
+use crate::register::mcycle;
+
+/// Latency statistics of a CLIC line, in `mcycle` cycles
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    /// Number of recorded handler entries
+    pub count: u32,
+    /// Number of handler entries with a raise timestamp, `min`, `max` and `last` are only valid if non-zero
+    pub samples: u32,
+    /// Smallest latency
+    pub min: u32,
+    /// Largest latency
+    pub max: u32,
+    /// Latency of the last handler entry with a raise timestamp
+    pub last: u32,
+}
+
+impl Stats {
+    const EMPTY: Stats = Stats {
+        count: 0,
+        samples: 0,
+        min: u32::MAX,
+        max: 0,
+        last: 0,
+    };
+}
+
+#[derive(Clone, Copy)]
+struct Line {
+    stats: Stats,
+    raised: Option<u64>,
+}
+
+impl Line {
+    const EMPTY: Line = Line {
+        stats: Stats::EMPTY,
+        raised: None,
+    };
+}
+
+trait Mark {
+    fn mark_nr(&self, nr: usize, timestamp: u64);
+}
+
+/// Table marked by `CLIC::pend`
+static INSTALLED: Mutex<Cell<Option<&'static (dyn Mark + Sync)>>> = Mutex::new(Cell::new(None));
+
+/// Marks `interrupt` in the installed table, if any
+#[inline]
+pub(crate) fn pended<I>(interrupt: I)
+where
+    I: InterruptNumber,
+{
+    critical_section::with(|cs| {
+        if let Some(table) = INSTALLED.borrow(cs).get() {
+            table.mark_nr(interrupt.number(), mcycle::read64());
+        }
+    })
+}
+
+/// Latency statistics of `N` CLIC lines
+pub struct LatencyTable<const N: usize> {
+    lines: Mutex<RefCell<[Line; N]>>,
+}
+
+impl<const N: usize> LatencyTable<N> {
+    /// Creates an empty table
+    #[inline]
+    pub const fn new() -> Self {
+        LatencyTable {
+            lines: Mutex::new(RefCell::new([Line::EMPTY; N])),
+        }
+    }
+
+    /// Makes `CLIC::pend` set the raise timestamps in this table
+    #[inline]
+    pub fn install(&'static self) {
+        critical_section::with(|cs| INSTALLED.borrow(cs).set(Some(self)));
+    }
+
+    /// Sets the `mcycle` timestamp at which `interrupt` was raised
+    #[inline]
+    pub fn mark<I>(&self, interrupt: I, timestamp: u64)
+    where
+        I: InterruptNumber,
+    {
+        self.mark_nr(interrupt.number(), timestamp)
+    }
+
+    /// Records an entry of `interrupt`, to be called at handler entry
+    ///
+    /// The latency is only measured if a raise timestamp was set.
+    #[inline]
+    pub fn record<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let now = mcycle::read64();
+        let nr = interrupt.number();
+        if nr >= N {
+            return;
+        }
+        critical_section::with(|cs| {
+            let line = &mut self.lines.borrow(cs).borrow_mut()[nr];
+            let stats = &mut line.stats;
+            stats.count = stats.count.wrapping_add(1);
+            if let Some(raised) = line.raised.take() {
+                let delta = now.wrapping_sub(raised).min(u32::MAX as u64) as u32;
+                stats.samples = stats.samples.wrapping_add(1);
+                stats.min = stats.min.min(delta);
+                stats.max = stats.max.max(delta);
+                stats.last = delta;
+            }
+        })
+    }
+
+    /// Returns the latency statistics of `interrupt`
+    ///
+    /// Returns `None` if `interrupt` was never recorded.
+    #[inline]
+    pub fn stats<I>(&self, interrupt: I) -> Option<Stats>
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        if nr >= N {
+            return None;
+        }
+        let stats = critical_section::with(|cs| self.lines.borrow(cs).borrow()[nr].stats);
+        if stats.count == 0 {
+            None
+        } else {
+            Some(stats)
+        }
+    }
+
+    /// Clears the latency statistics of `interrupt`
+    #[inline]
+    pub fn reset<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        if nr < N {
+            critical_section::with(|cs| self.lines.borrow(cs).borrow_mut()[nr] = Line::EMPTY);
+        }
+    }
+
+    /// Clears the latency statistics of all lines
+    #[inline]
+    pub fn reset_all(&self) {
+        critical_section::with(|cs| *self.lines.borrow(cs).borrow_mut() = [Line::EMPTY; N]);
+    }
+}
+
+impl<const N: usize> Default for LatencyTable<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Mark for LatencyTable<N> {
+    #[inline]
+    fn mark_nr(&self, nr: usize, timestamp: u64) {
+        if nr < N {
+            critical_section::with(|cs| self.lines.borrow(cs).borrow_mut()[nr].raised = Some(timestamp));
+        }
+    }
+}
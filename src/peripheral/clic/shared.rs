@@ -0,0 +1,117 @@
+//! Shared interrupt lines with chained handlers
+//!
+//! Several peripherals wired to the same CLIC input each register a handler on a
+//! [`SharedLine`], and the handler of the line calls [`SharedLine::dispatch`].
+//!
+//! ``` ignore
+//! static GPIO: SharedLine<Interrupt, 4> = SharedLine::new(Interrupt::GPIO, Dispatch::All);
+//!
+//! GPIO.register(button_handler).ok();
+//! GPIO.register(sensor_handler).ok();
+//!
+//! fn gpio_handler() {
+//!     GPIO.dispatch();
+//! }
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::clic::Trigger;
+use crate::peripheral::CLIC;
+
+/// Handler of a shared line, returns `true` if it handled the interrupt
+pub type Handler = fn() -> bool;
+
+/// Which handlers [`SharedLine::dispatch`] calls
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Dispatch {
+    /// Call every handler
+    All,
+    /// Call handlers until one handled the interrupt
+    UntilHandled,
+}
+
+/// CLIC line shared by up to `N` handlers
+pub struct SharedLine<I, const N: usize> {
+    interrupt: I,
+    dispatch: Dispatch,
+    handlers: Mutex<RefCell<[Option<Handler>; N]>>,
+}
+
+impl<I, const N: usize> SharedLine<I, N> {
+    /// Creates a line without handlers
+    #[inline]
+    pub const fn new(interrupt: I, dispatch: Dispatch) -> Self {
+        SharedLine {
+            interrupt,
+            dispatch,
+            handlers: Mutex::new(RefCell::new([None; N])),
+        }
+    }
+}
+
+impl<I, const N: usize> SharedLine<I, N>
+where
+    I: InterruptNumber,
+{
+    /// Adds `handler` after the registered handlers
+    ///
+    /// Returns `Err(handler)` if all `N` slots are used.
+    pub fn register(&self, handler: Handler) -> Result<(), Handler> {
+        critical_section::with(|cs| {
+            let mut handlers = self.handlers.borrow(cs).borrow_mut();
+            match handlers.iter_mut().find(|slot| slot.is_none()) {
+                Some(slot) => {
+                    *slot = Some(handler);
+                    Ok(())
+                }
+                None => Err(handler),
+            }
+        })
+    }
+
+    /// Removes `handler`, returns `false` if it was not registered
+    pub fn unregister(&self, handler: Handler) -> bool {
+        critical_section::with(|cs| {
+            let mut handlers = self.handlers.borrow(cs).borrow_mut();
+            let found = handlers
+                .iter()
+                .position(|slot| slot.map(|h| h as usize) == Some(handler as usize));
+            match found {
+                Some(i) => {
+                    // keep the registration order of the remaining handlers
+                    handlers[i..].rotate_left(1);
+                    handlers[N - 1] = None;
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Calls the registered handlers, to be called from the handler of the line
+    ///
+    /// The pending state of edge triggered lines is cleared before the handlers run,
+    /// so an edge arriving meanwhile pends the line again. Level triggered lines stay
+    /// pending until the handlers cleared their source. Returns `true` if a handler
+    /// handled the interrupt.
+    pub fn dispatch(&self) -> bool {
+        match CLIC::get_trig(self.interrupt) {
+            Trigger::EdgePositive | Trigger::EdgeNegative => CLIC::unpend(self.interrupt),
+            Trigger::LevelPositive | Trigger::LevelNegative => {}
+        }
+
+        let handlers = critical_section::with(|cs| *self.handlers.borrow(cs).borrow());
+        let mut handled = false;
+        for handler in handlers.iter().flatten() {
+            handled |= handler();
+            if handled && self.dispatch == Dispatch::UntilHandled {
+                break;
+            }
+        }
+        handled
+    }
+}
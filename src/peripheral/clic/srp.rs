@@ -0,0 +1,167 @@
+//! Stack Resource Policy scheduling on CLIC levels
+//!
+//! Logical task priorities start at 1 for the lowest priority task, priority 0 is
+//! the idle context. Each priority is mapped to a CLIC interrupt level using the
+//! configured `nlbits`, and resources are locked by raising `mintthresh` to the
+//! level of their ceiling.
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::CLIC;
+use crate::register::mintthresh;
+
+/// Mapping of logical priorities to CLIC interrupt levels
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Levels {
+    nlbits: u8,
+}
+
+impl Levels {
+    /// Creates the mapping for `nlbits` level bits
+    #[inline]
+    pub const fn new(nlbits: u8) -> Levels {
+        Levels {
+            nlbits: if nlbits > 8 { 8 } else { nlbits },
+        }
+    }
+
+    /// Creates the mapping for the `nlbits` currently configured in `cliccfg`
+    #[inline]
+    pub fn from_cfg() -> Levels {
+        Levels::new(CLIC::get_cfg().nlbits())
+    }
+
+    /// Returns the highest logical priority
+    #[inline]
+    pub fn max_priority(&self) -> u8 {
+        (1u16 << self.nlbits).min(u8::MAX as u16) as u8
+    }
+
+    /// Returns the 8 bit interrupt level of `priority`
+    ///
+    /// The unused lower level bits are filled with ones, as done by the hardware.
+    /// Priority 0 maps to level 0. Priorities above [`Levels::max_priority`] are saturated.
+    #[inline]
+    pub fn level(&self, priority: u8) -> u8 {
+        if priority == 0 {
+            return 0;
+        }
+        let priority = priority.min(self.max_priority());
+        if self.nlbits == 8 {
+            // without fill bits level 0 stays reserved for the idle context
+            return priority;
+        }
+        let fill = (0xFFu16 >> self.nlbits) as u8;
+        (((priority as u16 - 1) << (8 - self.nlbits)) as u8) | fill
+    }
+
+    /// Sets the level of `interrupt` to the level of `priority`
+    ///
+    /// # Safety
+    ///
+    /// Changing the level of `interrupt` can break critical sections locked through
+    /// a ceiling below its new priority.
+    #[inline]
+    pub unsafe fn set_priority<I>(&self, clic: &mut CLIC, interrupt: I, priority: u8)
+    where
+        I: InterruptNumber,
+    {
+        clic.set_priority(interrupt, self.level(priority))
+    }
+
+    /// Runs `f` with all tasks up to priority `ceiling` masked
+    ///
+    /// `mintthresh` is only raised if it is below the level of `ceiling`, and
+    /// restored after `f` returns.
+    #[inline]
+    pub fn lock<F, R>(&self, ceiling: u8, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let before = mintthresh::read();
+        let level = self.level(ceiling);
+        if (level as usize) <= before.get_thresh() {
+            return f();
+        }
+
+        let mut raised = before;
+        raised.set_thresh(level);
+        mintthresh::write(raised);
+
+        let r = f();
+
+        mintthresh::write(before);
+        r
+    }
+}
+
+/// Software task dispatcher on a spare CLIC line
+#[derive(Clone, Copy, Debug)]
+pub struct Dispatcher<I> {
+    interrupt: I,
+    priority: u8,
+}
+
+impl<I> Dispatcher<I> {
+    /// Creates a dispatcher running its tasks on `interrupt` at `priority`
+    #[inline]
+    pub const fn new(interrupt: I, priority: u8) -> Dispatcher<I> {
+        Dispatcher {
+            interrupt,
+            priority,
+        }
+    }
+}
+
+impl<I> Dispatcher<I>
+where
+    I: InterruptNumber,
+{
+    /// Sets up the line at the level of the dispatcher, see [`CLIC::enable_edge`]
+    ///
+    /// # Safety
+    ///
+    /// The line must not be used by any other handler, and the handler of the line
+    /// must run the tasks of this dispatcher.
+    #[inline]
+    pub unsafe fn init(&self, clic: &mut CLIC, levels: &Levels) {
+        clic.enable_edge(self.interrupt, levels.level(self.priority));
+    }
+
+    /// Returns the logical priority of the dispatcher
+    #[inline]
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// Requests the dispatcher to run
+    #[inline]
+    pub fn pend(&self) {
+        CLIC::pend(self.interrupt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Levels;
+
+    #[test]
+    fn levels() {
+        for nlbits in 0..=8u8 {
+            let levels = Levels::new(nlbits);
+            let max = levels.max_priority();
+            assert_eq!(max as u16, (1u16 << nlbits).min(255), "nlbits {}", nlbits);
+            assert_eq!(levels.level(0), 0);
+            assert_eq!(levels.level(max), u8::MAX, "nlbits {}", nlbits);
+            assert_eq!(levels.level(u8::MAX), u8::MAX, "nlbits {}", nlbits);
+
+            let mut below = 0;
+            for priority in 1..=max {
+                let level = levels.level(priority);
+                assert!(level > below, "nlbits {} priority {}", nlbits, priority);
+                let fill = (0xFFu16 >> nlbits) as u8;
+                assert_eq!(level & fill, fill, "nlbits {} priority {}", nlbits, priority);
+                below = level;
+            }
+        }
+    }
+}
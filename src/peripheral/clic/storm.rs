@@ -0,0 +1,185 @@
+//! Interrupt storm detection
+//!
+//! Handlers call [`StormGuard::enter`] first. If a line is taken more than the
+//! configured number of times within a window of `mcycle` cycles, it is masked, the
+//! event is recorded and the callback is invoked. The line stays masked until
+//! [`StormGuard::rearm`]. Window, limit and callback are shared by all lines of a
+//! guard, entry counts and detected storms are kept per line.
+//!
+//! ``` ignore
+//! static STORM: StormGuard<32> = StormGuard::new();
+//!
+//! STORM.configure(CORE_HZ / 100, 1_000, Some(log_storm));
+//!
+//! fn sensor_handler() {
+//!     if !STORM.enter(Interrupt::SENSOR) {
+//!         return;
+//!     }
+//!     // ...
+//! }
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::CLIC;
+use crate::register::mcycle;
+
+/// Callback invoked with the interrupt number of a masked line
+pub type Callback = fn(usize);
+
+#[derive(Clone, Copy)]
+struct Line {
+    window_start: u64,
+    count: u32,
+    tripped: bool,
+    events: u32,
+}
+
+impl Line {
+    const EMPTY: Line = Line {
+        window_start: 0,
+        count: 0,
+        tripped: false,
+        events: 0,
+    };
+}
+
+struct Guard<const N: usize> {
+    window: u64,
+    limit: u32,
+    callback: Option<Callback>,
+    lines: [Line; N],
+}
+
+/// Entry counters and storm events of `N` CLIC lines
+pub struct StormGuard<const N: usize> {
+    guard: Mutex<RefCell<Guard<N>>>,
+}
+
+impl<const N: usize> StormGuard<N> {
+    /// Creates a guard that masks no line until configured
+    #[inline]
+    pub const fn new() -> Self {
+        StormGuard {
+            guard: Mutex::new(RefCell::new(Guard {
+                window: 0,
+                limit: u32::MAX,
+                callback: None,
+                lines: [Line::EMPTY; N],
+            })),
+        }
+    }
+
+    /// Masks lines taken more than `limit` times within `window` `mcycle` cycles
+    ///
+    /// `callback` is invoked outside of the critical section after a line was masked.
+    #[inline]
+    pub fn configure(&self, window: u64, limit: u32, callback: Option<Callback>) {
+        critical_section::with(|cs| {
+            let mut guard = self.guard.borrow(cs).borrow_mut();
+            guard.window = window;
+            guard.limit = limit;
+            guard.callback = callback;
+        })
+    }
+
+    /// Counts an entry of `interrupt`, to be called at handler entry
+    ///
+    /// Returns `false` if the line was masked because of a storm, the handler
+    /// should then return without servicing it.
+    pub fn enter<I>(&self, interrupt: I) -> bool
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        if nr >= N {
+            return true;
+        }
+        let now = mcycle::read64();
+
+        let tripped = critical_section::with(|cs| {
+            let mut guard = self.guard.borrow(cs).borrow_mut();
+            let (window, limit, callback) = (guard.window, guard.limit, guard.callback);
+            let line = &mut guard.lines[nr];
+
+            if now.wrapping_sub(line.window_start) > window {
+                line.window_start = now;
+                line.count = 0;
+            }
+            line.count = line.count.saturating_add(1);
+            if line.count > limit && !line.tripped {
+                line.tripped = true;
+                line.events = line.events.wrapping_add(1);
+                CLIC::mask(interrupt);
+                Some(callback)
+            } else {
+                None
+            }
+        });
+
+        match tripped {
+            Some(callback) => {
+                if let Some(callback) = callback {
+                    callback(nr);
+                }
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Checks if `interrupt` was masked because of a storm
+    #[inline]
+    pub fn is_tripped<I>(&self, interrupt: I) -> bool
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        nr < N && critical_section::with(|cs| self.guard.borrow(cs).borrow().lines[nr].tripped)
+    }
+
+    /// Returns how many storms were detected on `interrupt`
+    #[inline]
+    pub fn events<I>(&self, interrupt: I) -> u32
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        if nr >= N {
+            return 0;
+        }
+        critical_section::with(|cs| self.guard.borrow(cs).borrow().lines[nr].events)
+    }
+
+    /// Restarts counting on `interrupt` and unmasks it if it was masked because of a storm
+    pub fn rearm<I>(&self, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        if nr >= N {
+            return;
+        }
+        let tripped = critical_section::with(|cs| {
+            let line = &mut self.guard.borrow(cs).borrow_mut().lines[nr];
+            let tripped = line.tripped;
+            line.tripped = false;
+            line.count = 0;
+            line.window_start = mcycle::read64();
+            tripped
+        });
+        if tripped {
+            CLIC::unmask(interrupt);
+        }
+    }
+}
+
+impl<const N: usize> Default for StormGuard<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
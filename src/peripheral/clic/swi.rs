@@ -0,0 +1,188 @@
+//! Deferred work queues on spare CLIC lines
+//!
+//! A [`SoftwareQueue`] turns an unused CLIC line into a queue of jobs. Jobs can be
+//! posted from any context, including higher level interrupt handlers, and are run
+//! by the handler of the line at its own level.
+//!
+//! ``` ignore
+//! static QUEUE: SoftwareQueue<Interrupt, Job, 8> = SoftwareQueue::new(Interrupt::SWI0);
+//!
+//! fn uart_handler() {
+//!     // keep the high level handler short
+//!     QUEUE.post(Job::Parse).ok();
+//! }
+//!
+//! fn swi0_handler() {
+//!     QUEUE.drain(|job| job.run());
+//! }
+//! ```
+//!
+//! The queue is lock-free and requires atomic compare-and-swap instructions
+//! (the `A` extension).
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::CLIC;
+
+const EMPTY: u8 = 0;
+const FULL: u8 = 1;
+
+/// Fixed-capacity queue of jobs run by the handler of a CLIC line
+pub struct SoftwareQueue<I, T, const N: usize> {
+    interrupt: I,
+    /// Index of the next job to run, modulo `2 * N`
+    head: AtomicUsize,
+    /// Index of the next free slot, modulo `2 * N`
+    tail: AtomicUsize,
+    draining: AtomicBool,
+    states: [AtomicU8; N],
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+}
+
+// NOTE jobs are moved between contexts, slots are only accessed by the context owning their index
+unsafe impl<I, T, const N: usize> Sync for SoftwareQueue<I, T, N>
+where
+    I: Sync,
+    T: Send,
+{
+}
+
+impl<I, T, const N: usize> SoftwareQueue<I, T, N> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const STATE: AtomicU8 = AtomicU8::new(EMPTY);
+    #[allow(clippy::declare_interior_mutable_const)]
+    const SLOT: UnsafeCell<MaybeUninit<T>> = UnsafeCell::new(MaybeUninit::uninit());
+
+    /// Creates an empty queue run on `interrupt`
+    #[inline]
+    pub const fn new(interrupt: I) -> Self {
+        SoftwareQueue {
+            interrupt,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            draining: AtomicBool::new(false),
+            states: [Self::STATE; N],
+            slots: [Self::SLOT; N],
+        }
+    }
+
+    /// Returns the capacity of the queue
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the index following `index`
+    ///
+    /// Indices wrap at `2 * N` rather than `usize::MAX`, which keeps the slot of an
+    /// index and the distance between two indices valid for any `N`.
+    #[inline]
+    fn next(index: usize) -> usize {
+        if index + 1 == 2 * N {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    /// Returns the number of indices from `head` to `tail`
+    #[inline]
+    fn distance(head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + 2 * N - head
+        }
+    }
+}
+
+impl<I, T, const N: usize> SoftwareQueue<I, T, N>
+where
+    I: InterruptNumber,
+{
+    /// Sets up the line with the raw `clicintctl` value `ctl`, see [`CLIC::enable_edge`]
+    ///
+    /// # Safety
+    ///
+    /// The line must not be used by any other handler, and the handler of the line
+    /// must drain this queue.
+    #[inline]
+    pub unsafe fn init(&self, clic: &mut CLIC, ctl: u8) {
+        clic.enable_edge(self.interrupt, ctl);
+    }
+
+    /// Posts `job` and pends the line
+    ///
+    /// Returns `Err(job)` if the queue is full.
+    pub fn post(&self, job: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            if Self::distance(head, tail) >= N {
+                return Err(job);
+            }
+            match self.tail.compare_exchange_weak(
+                tail,
+                Self::next(tail),
+                Ordering::AcqRel,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => tail = current,
+            }
+        }
+
+        let i = tail % N;
+        // NOTE(unsafe) the slot was released by the consumer and is reserved for this context
+        unsafe { (*self.slots[i].get()).as_mut_ptr().write(job) };
+        self.states[i].store(FULL, Ordering::Release);
+
+        CLIC::pend(self.interrupt);
+        Ok(())
+    }
+
+    /// Runs `f` on every posted job in order, to be called from the handler of the line
+    ///
+    /// Jobs whose post has not completed yet are left for the pend that completes it.
+    /// Nested calls return immediately.
+    pub fn drain<F>(&self, mut f: F)
+    where
+        F: FnMut(T),
+    {
+        if self.draining.swap(true, Ordering::Acquire) {
+            return;
+        }
+
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            let i = head % N;
+            if self.states[i].load(Ordering::Acquire) != FULL {
+                break;
+            }
+            // NOTE(unsafe) the slot was published by a producer and is owned by the consumer
+            let job = unsafe { (*self.slots[i].get()).as_ptr().read() };
+            self.states[i].store(EMPTY, Ordering::Relaxed);
+            head = Self::next(head);
+            self.head.store(head, Ordering::Release);
+            f(job);
+        }
+
+        self.draining.store(false, Ordering::Release);
+    }
+
+    /// Returns the number of posted jobs that have not been run yet
+    #[inline]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        Self::distance(head, self.tail.load(Ordering::Acquire)).min(N)
+    }
+
+    /// Checks if no jobs are queued
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
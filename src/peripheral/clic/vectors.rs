@@ -0,0 +1,146 @@
+//! Runtime handler registration with a vector table in RAM
+//!
+//! A [`RamVectorTable`] takes over the current `mtvt` table so handlers of
+//! selectively hardware vectored interrupts can be changed at runtime.
+//!
+//! ``` ignore
+//! static TABLE: RamVectorTable<64> = RamVectorTable::new();
+//!
+//! unsafe {
+//!     TABLE.install().unwrap();
+//!     TABLE.register_handler(&mut p.CLIC, Interrupt::GPIO, gpio_trap_handler);
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::CLIC;
+use crate::register::mtvt;
+
+/// Vector table with `N` entries, aligned for `mtvt`
+///
+/// Implementations may require a larger alignment than 64 bytes for large tables,
+/// [`RamVectorTable::install`] fails if `mtvt` does not keep the address of the table.
+#[repr(C, align(64))]
+pub struct RamVectorTable<const N: usize> {
+    entries: UnsafeCell<[usize; N]>,
+    /// Base of the table copied by `install`
+    original: UnsafeCell<usize>,
+}
+
+// NOTE entries are only written inside critical sections
+unsafe impl<const N: usize> Sync for RamVectorTable<N> {}
+
+impl<const N: usize> Default for RamVectorTable<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> RamVectorTable<N> {
+    /// Creates an empty table
+    #[inline]
+    pub const fn new() -> Self {
+        RamVectorTable {
+            entries: UnsafeCell::new([0; N]),
+            original: UnsafeCell::new(0),
+        }
+    }
+
+    /// Copies the first `N` entries of the current `mtvt` table and switches `mtvt` to this table
+    ///
+    /// Returns `Err` with the base read back from `mtvt` if the implementation does not
+    /// keep the address of this table, e.g. because it needs a larger alignment. The
+    /// current table stays in use in that case.
+    ///
+    /// # Safety
+    ///
+    /// The current table must have at least `N` entries and must stay in place, as
+    /// [`RamVectorTable::unregister_handler`] restores entries from it.
+    pub unsafe fn install(&'static self) -> Result<(), usize> {
+        let current = mtvt::read().get_base();
+        critical_section::with(|_| {
+            let entries = &mut *self.entries.get();
+            for (i, entry) in entries.iter_mut().enumerate() {
+                *entry = (current as *const usize).add(i).read_volatile();
+            }
+            let base = entries.as_ptr() as usize;
+            mtvt::write_addr(base);
+            let kept = mtvt::read().get_base();
+            if kept == base {
+                *self.original.get() = current;
+                Ok(())
+            } else {
+                mtvt::write_addr(current);
+                Err(kept)
+            }
+        })
+    }
+
+    /// Returns the entry of `interrupt`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of `interrupt` is not smaller than `N`.
+    #[inline]
+    pub fn handler<I>(&self, interrupt: I) -> usize
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        assert!(nr < N);
+        critical_section::with(|_| unsafe { (*self.entries.get())[nr] })
+    }
+
+    /// Sets the entry of `interrupt` to `handler` and enables its selective hardware vectoring
+    ///
+    /// # Safety
+    ///
+    /// The table must be installed. `handler` is entered directly from the trap and
+    /// has to save and restore the context itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of `interrupt` is not smaller than `N`.
+    pub unsafe fn register_handler<I>(
+        &self,
+        clic: &mut CLIC,
+        interrupt: I,
+        handler: unsafe extern "C" fn(),
+    ) where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        assert!(nr < N);
+        critical_section::with(|_| {
+            (*self.entries.get())[nr] = handler as usize;
+            clic.enable_shv(interrupt);
+        })
+    }
+
+    /// Disables selective hardware vectoring of `interrupt`, it is handled by the common trap handler again
+    ///
+    /// The entry of `interrupt` is restored from the table copied by [`RamVectorTable::install`].
+    ///
+    /// # Safety
+    ///
+    /// The table must be installed, and the common trap handler has to dispatch `interrupt`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of `interrupt` is not smaller than `N`.
+    pub unsafe fn unregister_handler<I>(&self, clic: &mut CLIC, interrupt: I)
+    where
+        I: InterruptNumber,
+    {
+        let nr = interrupt.number();
+        assert!(nr < N);
+        critical_section::with(|_| {
+            clic.disable_shv(interrupt);
+            let original = *self.original.get() as *const usize;
+            (*self.entries.get())[nr] = original.add(nr).read_volatile();
+        })
+    }
+}
@@ -2,6 +2,8 @@ pub mod clic;
 pub mod syst;
 use core::{ops, marker::PhantomData};
 
+use crate::register::mhartid;
+
 
 pub struct Peripherals {
     pub CLIC: CLIC,
@@ -17,6 +19,17 @@ static CORE_PERIPHERALS: () = ();
 /// Set to `true` when `take` or `steal` was called to make `Peripherals` a singleton.
 static mut TAKEN: bool = false;
 
+/// Set to `true` when `take` or `steal` was called to make `ModePeripherals` a singleton.
+static mut MODE_TAKEN: bool = false;
+
+/// Maximum number of harts with their own `HartPeripherals`
+const MAX_HARTS: usize = 64;
+
+/// Entry `n` is set when `take` or `steal` was called on hart `n` to make its `HartPeripherals` a singleton.
+///
+/// NOTE each hart only writes its own entry, the critical section only covers the current hart
+static mut HART_TAKEN: [bool; MAX_HARTS] = [false; MAX_HARTS];
+
 impl Peripherals {
     /// Returns all the core peripherals *once*
     #[inline]
@@ -47,12 +60,158 @@ impl Peripherals {
     }
 }
 
+/// Core peripherals private to a single hart, with the CLIC placed by the SoC layout `L`
+///
+/// A `HartCLIC` only offers the per-line operations, shared with `CLIC` through
+/// [`clic::InterruptBlock`]. The helpers in the `clic` and `syst` submodules drive the
+/// CLIC at `CLIC::PTR` and cannot be used with the CLIC of another hart.
+#[allow(non_snake_case)]
+#[non_exhaustive]
+pub struct HartPeripherals<L> {
+    pub CLIC: HartCLIC<L>,
+}
+
+impl<L> HartPeripherals<L>
+where
+    L: ClicLayout,
+{
+    /// Maximum number of harts supported
+    pub const MAX_HARTS: usize = MAX_HARTS;
+
+    /// Returns the core peripherals of the current hart *once*
+    ///
+    /// Returns `None` for harts whose CLIC overlaps the one owned by `Peripherals::CLIC`.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        let hartid = mhartid::read();
+        if hartid >= Self::MAX_HARTS || HartCLIC::<L>::aliases_clic(hartid) {
+            return None;
+        }
+        critical_section::with(|_| {
+            if unsafe { HART_TAKEN[hartid] } {
+                None
+            } else {
+                Some(unsafe { HartPeripherals::steal() })
+            }
+        })
+    }
+
+    /// Unchecked version of `HartPeripherals::take`
+    ///
+    /// # Safety
+    ///
+    /// The returned peripherals may alias ones that were already taken, on this hart or
+    /// through `Peripherals::CLIC`. Harts beyond `MAX_HARTS` are not tracked at all.
+    #[inline]
+    pub unsafe fn steal() -> Self {
+        let hartid = mhartid::read();
+        if hartid < Self::MAX_HARTS {
+            HART_TAKEN[hartid] = true;
+        }
+
+        HartPeripherals {
+            CLIC: HartCLIC {
+                hartid,
+                _marker: PhantomData,
+            },
+        }
+    }
+}
+
+/// Less privileged CLIC windows, placed by the SoC layout `L`
+#[allow(non_snake_case)]
+#[non_exhaustive]
+pub struct ModePeripherals<L> {
+    pub SCLIC: SupervisorCLIC<L>,
+    pub UCLIC: UserCLIC<L>,
+}
+
+impl<L> ModePeripherals<L>
+where
+    L: ClicLayout,
+{
+    /// Returns the supervisor and user mode windows *once*
+    ///
+    /// Returns `None` if a window overlaps the CLIC owned by `Peripherals::CLIC`.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        let size = core::mem::size_of::<clic::ModeRegisterBlock>();
+        if overlaps_clic(L::SUPERVISOR_BASE, size) || overlaps_clic(L::USER_BASE, size) {
+            return None;
+        }
+        critical_section::with(|_| {
+            if unsafe { MODE_TAKEN } {
+                None
+            } else {
+                Some(unsafe { ModePeripherals::steal() })
+            }
+        })
+    }
+
+    /// Unchecked version of `ModePeripherals::take`
+    ///
+    /// # Safety
+    ///
+    /// The returned windows may alias ones that were already taken.
+    #[inline]
+    pub unsafe fn steal() -> Self {
+        MODE_TAKEN = true;
+
+        ModePeripherals {
+            SCLIC: SupervisorCLIC {
+                _marker: PhantomData,
+            },
+            UCLIC: UserCLIC {
+                _marker: PhantomData,
+            },
+        }
+    }
+}
+
+/// Placement of the CLIC register blocks on a SoC
+///
+/// The CLIC specification leaves these addresses to the platform.
+pub trait ClicLayout {
+    /// Base address of the register block of hart 0
+    const HART_BASE: usize;
+    /// Distance between the register blocks of two consecutive harts
+    const HART_STRIDE: usize;
+    /// Base address of the supervisor mode window
+    const SUPERVISOR_BASE: usize;
+    /// Base address of the user mode window
+    const USER_BASE: usize;
+}
+
+/// Checks if `size` bytes at `base` overlap the register block at `CLIC::PTR`
+fn overlaps_clic(base: usize, size: usize) -> bool {
+    let clic = CLIC::PTR as usize;
+    base < clic + core::mem::size_of::<clic::RegisterBlock>() && clic < base + size
+}
+
 /// Nested Vector Interrupt Controller
 #[allow(clippy::upper_case_acronyms)]
 pub struct CLIC {
     _marker: PhantomData<*const ()>,
 }
 
+/// Supervisor mode window of the CLIC
+#[allow(clippy::upper_case_acronyms)]
+pub struct SupervisorCLIC<L> {
+    _marker: PhantomData<(*const (), L)>,
+}
+
+/// User mode window of the CLIC
+#[allow(clippy::upper_case_acronyms)]
+pub struct UserCLIC<L> {
+    _marker: PhantomData<(*const (), L)>,
+}
+
+/// Core Local Interrupt Controller of a single hart
+#[allow(clippy::upper_case_acronyms)]
+pub struct HartCLIC<L> {
+    hartid: usize,
+    _marker: PhantomData<(*const (), L)>,
+}
 
 /// SYSTEM TIMER
 #[allow(clippy::upper_case_acronyms)]
@@ -77,6 +236,91 @@ impl ops::Deref for CLIC {
     }
 }
 
+unsafe impl<L> Send for SupervisorCLIC<L> {}
+
+impl<L> SupervisorCLIC<L>
+where
+    L: ClicLayout,
+{
+    /// Pointer to the register block
+    pub const PTR: *const clic::ModeRegisterBlock = L::SUPERVISOR_BASE as *const _;
+}
+
+impl<L> ops::Deref for SupervisorCLIC<L>
+where
+    L: ClicLayout,
+{
+    type Target = self::clic::ModeRegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*Self::PTR }
+    }
+}
+
+unsafe impl<L> Send for UserCLIC<L> {}
+
+impl<L> UserCLIC<L>
+where
+    L: ClicLayout,
+{
+    /// Pointer to the register block
+    pub const PTR: *const clic::ModeRegisterBlock = L::USER_BASE as *const _;
+}
+
+impl<L> ops::Deref for UserCLIC<L>
+where
+    L: ClicLayout,
+{
+    type Target = self::clic::ModeRegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*Self::PTR }
+    }
+}
+
+unsafe impl<L> Send for HartCLIC<L> {}
+
+impl<L> HartCLIC<L>
+where
+    L: ClicLayout,
+{
+    /// Returns the id of the hart this CLIC belongs to
+    #[inline]
+    pub fn hartid(&self) -> usize {
+        self.hartid
+    }
+
+    /// Pointer to the register block of this hart
+    #[inline]
+    pub fn ptr(&self) -> *const clic::RegisterBlock {
+        Self::ptr_of(self.hartid)
+    }
+
+    #[inline]
+    fn ptr_of(hartid: usize) -> *const clic::RegisterBlock {
+        (L::HART_BASE + hartid * L::HART_STRIDE) as *const _
+    }
+
+    /// Checks if the register block of `hartid` overlaps the one at `CLIC::PTR`
+    fn aliases_clic(hartid: usize) -> bool {
+        let size = core::mem::size_of::<clic::RegisterBlock>();
+        overlaps_clic(Self::ptr_of(hartid) as usize, size)
+    }
+}
+
+impl<L> ops::Deref for HartCLIC<L>
+where
+    L: ClicLayout,
+{
+    type Target = self::clic::RegisterBlock;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr() }
+    }
+}
 
 unsafe impl Send for SYST {}
 
@@ -1,8 +1,20 @@
 //! SysTick: System Timer
 
+use core::marker::PhantomData;
+use core::time::Duration;
+
 use volatile_register::{RW,WO};
 
-use crate::peripheral::SYST;
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::{CLIC, SYST};
+
+pub mod alarm;
+#[cfg(feature = "syst-embassy")]
+pub mod embassy;
+pub mod idle;
+#[cfg(feature = "syst-monotonic")]
+pub mod monotonic;
+pub mod timer;
 
 /// Writes the `bits` into `base_values` at pos [low_bit, high_bit] both included
 #[inline]
@@ -51,7 +63,732 @@ pub struct RegisterBlock {
     pub reset_high: WO<u32>,
 }
 
+/// Counting mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Keep counting after reaching the compare value
+    Continuous = 0,
+    /// Restart from zero after reaching the compare value
+    Cycle = 1,
+}
+
+/// Clock source
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClockSource {
+    /// FLL clock
+    Fll = 0,
+    /// Reference clock
+    Reference = 1,
+}
+
+/// Configuration register of a timer half
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TimerConfig {
+    bits: u32,
+}
+
+impl TimerConfig {
+    /// Creates a configuration with every bit cleared
+    #[inline]
+    pub const fn new() -> Self {
+        TimerConfig { bits: 0 }
+    }
+
+    /// Creates a configuration from raw bits
+    #[inline]
+    pub const fn from_bits(bits: u32) -> Self {
+        TimerConfig { bits }
+    }
+
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Is the timer counting
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        read_bits(self.bits, 0, 0) == 1
+    }
+
+    /// Sets whether the timer is counting
+    #[inline]
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 0, 0, enabled as u32);
+    }
+
+    /// Is the compare interrupt enabled
+    #[inline]
+    pub fn interrupt(&self) -> bool {
+        read_bits(self.bits, 2, 2) == 1
+    }
+
+    /// Sets whether the compare interrupt is enabled
+    #[inline]
+    pub fn set_interrupt(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 2, 2, enabled as u32);
+    }
+
+    /// Is the compare event enabled
+    #[inline]
+    pub fn event_mask(&self) -> bool {
+        read_bits(self.bits, 3, 3) == 1
+    }
+
+    /// Sets whether the compare event is enabled
+    #[inline]
+    pub fn set_event_mask(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 3, 3, enabled as u32);
+    }
+
+    /// Returns the counting mode
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        match read_bits(self.bits, 4, 4) {
+            0 => Mode::Continuous,
+            _ => Mode::Cycle,
+        }
+    }
+
+    /// Sets the counting mode
+    #[inline]
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.bits = write_bits(self.bits, 4, 4, mode as u32);
+    }
+
+    /// Does the timer stop after reaching the compare value
+    #[inline]
+    pub fn one_shot(&self) -> bool {
+        read_bits(self.bits, 5, 5) == 1
+    }
+
+    /// Sets whether the timer stops after reaching the compare value
+    #[inline]
+    pub fn set_one_shot(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 5, 5, enabled as u32);
+    }
+
+    /// Is the prescaler enabled
+    #[inline]
+    pub fn prescaler_enabled(&self) -> bool {
+        read_bits(self.bits, 6, 6) == 1
+    }
+
+    /// Sets whether the prescaler is enabled
+    #[inline]
+    pub fn set_prescaler_enabled(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 6, 6, enabled as u32);
+    }
+
+    /// Returns the clock source
+    #[inline]
+    pub fn clock_source(&self) -> ClockSource {
+        match read_bits(self.bits, 7, 7) {
+            0 => ClockSource::Fll,
+            _ => ClockSource::Reference,
+        }
+    }
+
+    /// Sets the clock source
+    #[inline]
+    pub fn set_clock_source(&mut self, source: ClockSource) {
+        self.bits = write_bits(self.bits, 7, 7, source as u32);
+    }
+
+    /// Returns the prescaler value
+    #[inline]
+    pub fn prescaler(&self) -> u8 {
+        read_bits(self.bits, 15, 8) as u8
+    }
+
+    /// Sets the prescaler value
+    #[inline]
+    pub fn set_prescaler(&mut self, value: u8) {
+        self.bits = write_bits(self.bits, 15, 8, value as u32);
+    }
+
+    /// Are both halves cascaded into a 64 bit timer, only used by the low half
+    #[inline]
+    pub fn cascaded(&self) -> bool {
+        read_bits(self.bits, 31, 31) == 1
+    }
+
+    /// Sets whether both halves are cascaded into a 64 bit timer, only used by the low half
+    #[inline]
+    pub fn set_cascaded(&mut self, enabled: bool) {
+        self.bits = write_bits(self.bits, 31, 31, enabled as u32);
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Half of the timer, [`Lo`] or [`Hi`]
+pub trait Half: sealed::Sealed {
+    #[doc(hidden)]
+    const HIGH: bool;
+    #[doc(hidden)]
+    fn cfg(block: &RegisterBlock) -> &RW<u32>;
+    #[doc(hidden)]
+    fn cnt(block: &RegisterBlock) -> &RW<u32>;
+    #[doc(hidden)]
+    fn cmp(block: &RegisterBlock) -> &RW<u32>;
+    #[doc(hidden)]
+    fn start(block: &RegisterBlock) -> &WO<u32>;
+    #[doc(hidden)]
+    fn reset(block: &RegisterBlock) -> &WO<u32>;
+}
+
+/// Low half of the timer
+pub struct Lo;
+
+/// High half of the timer
+pub struct Hi;
+
+impl sealed::Sealed for Lo {}
+impl sealed::Sealed for Hi {}
+
+impl Half for Lo {
+    const HIGH: bool = false;
+
+    #[inline(always)]
+    fn cfg(block: &RegisterBlock) -> &RW<u32> {
+        &block.cfg_low
+    }
+    #[inline(always)]
+    fn cnt(block: &RegisterBlock) -> &RW<u32> {
+        &block.cnt_low
+    }
+    #[inline(always)]
+    fn cmp(block: &RegisterBlock) -> &RW<u32> {
+        &block.cmp_low
+    }
+    #[inline(always)]
+    fn start(block: &RegisterBlock) -> &WO<u32> {
+        &block.start_low
+    }
+    #[inline(always)]
+    fn reset(block: &RegisterBlock) -> &WO<u32> {
+        &block.reset_low
+    }
+}
+
+impl Half for Hi {
+    const HIGH: bool = true;
+
+    #[inline(always)]
+    fn cfg(block: &RegisterBlock) -> &RW<u32> {
+        &block.cfg_high
+    }
+    #[inline(always)]
+    fn cnt(block: &RegisterBlock) -> &RW<u32> {
+        &block.cnt_high
+    }
+    #[inline(always)]
+    fn cmp(block: &RegisterBlock) -> &RW<u32> {
+        &block.cmp_high
+    }
+    #[inline(always)]
+    fn start(block: &RegisterBlock) -> &WO<u32> {
+        &block.start_high
+    }
+    #[inline(always)]
+    fn reset(block: &RegisterBlock) -> &WO<u32> {
+        &block.reset_high
+    }
+}
+
+/// Prescaler and compare pair found by [`TimerHalf::configure_rate`] and [`TimerHalf::configure_period`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TickRate {
+    /// Prescaler value, the clock is divided by this value plus one
+    pub prescaler: u8,
+    /// Compare value
+    pub compare: u32,
+    /// Achieved rate of compare events in millihertz, rounded to the nearest millihertz
+    pub achieved_mhz: u64,
+    /// Deviation of the achieved period from the requested one, in parts per million
+    pub error_ppm: i32,
+}
+
+/// Error of [`TimerHalf::configure_rate`] and [`TimerHalf::configure_period`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RateError {
+    /// A frequency or the period is zero
+    Zero,
+    /// The requested period is shorter than one clock cycle
+    TooFast,
+    /// The requested period does not fit in the compare register with the largest prescaler
+    TooSlow,
+}
+
+/// Finds the prescaler and compare pair whose period is closest to `num / den` clock cycles
+///
+/// Ties are resolved in favour of the smallest prescaler, which has the finest resolution.
+fn best_rate(source_hz: u32, num: u128, den: u128) -> Result<TickRate, RateError> {
+    if source_hz == 0 || num == 0 || den == 0 {
+        return Err(RateError::Zero);
+    }
+    if num < den {
+        return Err(RateError::TooFast);
+    }
+
+    let mut best: Option<(u8, u32, u128)> = None;
+    for prescaler in 0..=u8::MAX {
+        let divider = den * (prescaler as u128 + 1);
+        let compare = ((num + divider / 2) / divider).max(1);
+        if compare > u32::MAX as u128 {
+            continue;
+        }
+        let achieved = compare * divider;
+        let error = if achieved > num { achieved - num } else { num - achieved };
+        if best.map_or(true, |(_, _, best_error)| error < best_error) {
+            best = Some((prescaler, compare as u32, error));
+        }
+        if error == 0 {
+            break;
+        }
+    }
+
+    let (prescaler, compare, _) = best.ok_or(RateError::TooSlow)?;
+    let cycles = compare as u128 * (prescaler as u128 + 1);
+    // achieved period is `cycles`, requested period is `num / den` cycles
+    let error_ppm = (cycles as i128 * den as i128 - num as i128) * 1_000_000 / num as i128;
+    Ok(TickRate {
+        prescaler,
+        compare,
+        achieved_mhz: ((source_hz as u128 * 1_000 + cycles / 2) / cycles) as u64,
+        error_ppm: error_ppm as i32,
+    })
+}
+
+/// CLIC lines the compare events of the timer halves are wired to on a SoC
+pub trait SystLines {
+    /// Line of the low half
+    const LO: usize;
+    /// Line of the high half
+    const HI: usize;
+}
+
+/// CLIC lines of the timer on PULP SoCs
+pub struct PulpLines;
+
+impl SystLines for PulpLines {
+    const LO: usize = 10;
+    const HI: usize = 11;
+}
+
+/// Compare interrupt of a timer half, on the CLIC lines given by `L`
+pub struct SystInterrupt<L = PulpLines> {
+    high: bool,
+    _lines: PhantomData<fn() -> L>,
+}
+
+impl<L> SystInterrupt<L> {
+    /// Interrupt of the low half
+    pub const LO: Self = SystInterrupt {
+        high: false,
+        _lines: PhantomData,
+    };
+
+    /// Interrupt of the high half
+    pub const HI: Self = SystInterrupt {
+        high: true,
+        _lines: PhantomData,
+    };
+}
+
+impl<L> Clone for SystInterrupt<L> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L> Copy for SystInterrupt<L> {}
+
+impl<L> PartialEq for SystInterrupt<L> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.high == other.high
+    }
+}
+
+impl<L> Eq for SystInterrupt<L> {}
+
+impl<L> core::fmt::Debug for SystInterrupt<L> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(if self.high { "SystInterrupt::HI" } else { "SystInterrupt::LO" })
+    }
+}
+
+unsafe impl<L> InterruptNumber for SystInterrupt<L>
+where
+    L: SystLines,
+{
+    #[inline]
+    fn number(self) -> usize {
+        if self.high {
+            L::HI
+        } else {
+            L::LO
+        }
+    }
+
+    #[inline]
+    fn from_number(n: usize) -> Result<Self, usize> {
+        if n == L::LO {
+            Ok(Self::LO)
+        } else if n == L::HI {
+            Ok(Self::HI)
+        } else {
+            Err(n)
+        }
+    }
+}
+
+/// Half of the timer split off from [`SYST`], owning its registers
+pub struct TimerHalf<H> {
+    _marker: PhantomData<(*const (), H)>,
+}
+
+unsafe impl<H> Send for TimerHalf<H> {}
+
+impl<H> TimerHalf<H>
+where
+    H: Half,
+{
+    #[inline(always)]
+    fn block(&self) -> &RegisterBlock {
+        unsafe { &*SYST::PTR }
+    }
+
+    /// Reads the configuration
+    #[inline]
+    pub fn get_config(&self) -> TimerConfig {
+        TimerConfig::from_bits(H::cfg(self.block()).read())
+    }
+
+    /// Writes the configuration
+    #[inline]
+    pub fn set_config(&mut self, config: TimerConfig) {
+        unsafe { H::cfg(self.block()).write(config.bits) }
+    }
+
+    /// Modifies the configuration with a single register write
+    #[inline]
+    pub fn modify_config<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut TimerConfig),
+    {
+        let mut config = self.get_config();
+        f(&mut config);
+        self.set_config(config)
+    }
+
+    /// Returns the counter value
+    #[inline]
+    pub fn get_counter(&self) -> u32 {
+        H::cnt(self.block()).read()
+    }
+
+    /// Sets the counter value
+    #[inline]
+    pub fn set_counter(&mut self, value: u32) {
+        unsafe { H::cnt(self.block()).write(value) }
+    }
+
+    /// Returns the compare value
+    #[inline]
+    pub fn get_compare(&self) -> u32 {
+        H::cmp(self.block()).read()
+    }
+
+    /// Sets the compare value
+    #[inline]
+    pub fn set_compare(&mut self, value: u32) {
+        unsafe { H::cmp(self.block()).write(value) }
+    }
+
+    /// Starts counting through the start command register
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe { H::start(self.block()).write(1) }
+    }
+
+    /// Resets the counter through the reset command register
+    #[inline]
+    pub fn reset(&mut self) {
+        unsafe { H::reset(self.block()).write(1) }
+    }
+
+    /// Stops counting
+    #[inline]
+    pub fn stop(&mut self) {
+        self.modify_config(|config| config.set_enabled(false))
+    }
+
+    /// Returns the compare interrupt of this half
+    #[inline]
+    pub fn interrupt<L>(&self) -> SystInterrupt<L>
+    where
+        L: SystLines,
+    {
+        if H::HIGH {
+            SystInterrupt::HI
+        } else {
+            SystInterrupt::LO
+        }
+    }
+
+    /// Enables the compare interrupt and sets up its CLIC line with the raw `clicintctl` value `ctl`
+    ///
+    /// See [`CLIC::enable_edge`].
+    ///
+    /// # Safety
+    ///
+    /// Unmasking the line can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn enable_clic_interrupt<L>(&mut self, clic: &mut CLIC, ctl: u8)
+    where
+        L: SystLines,
+    {
+        self.modify_config(|config| config.set_interrupt(true));
+        clic.enable_edge(self.interrupt::<L>(), ctl);
+    }
+
+    /// Masks the CLIC line of the compare interrupt and disables the interrupt
+    #[inline]
+    pub fn disable_clic_interrupt<L>(&mut self)
+    where
+        L: SystLines,
+    {
+        CLIC::mask(self.interrupt::<L>());
+        self.modify_config(|config| config.set_interrupt(false));
+    }
+
+    /// Configures the half to raise compare events at `tick_hz`, clocked at `source_hz`
+    ///
+    /// The half is set to cycle mode with the best prescaler and compare pair and its
+    /// counter is reset, whether it is counting is left unchanged.
+    pub fn configure_rate(&mut self, source_hz: u32, tick_hz: u32) -> Result<TickRate, RateError> {
+        let rate = best_rate(source_hz, source_hz as u128, tick_hz as u128)?;
+        self.apply_rate(&rate);
+        Ok(rate)
+    }
+
+    /// Configures the half to raise a compare event every `period`, clocked at `source_hz`
+    ///
+    /// The half is set to cycle mode with the best prescaler and compare pair and its
+    /// counter is reset, whether it is counting is left unchanged.
+    pub fn configure_period(
+        &mut self,
+        source_hz: u32,
+        period: Duration,
+    ) -> Result<TickRate, RateError> {
+        let rate = best_rate(
+            source_hz,
+            source_hz as u128 * period.as_nanos(),
+            1_000_000_000,
+        )?;
+        self.apply_rate(&rate);
+        Ok(rate)
+    }
+
+    fn apply_rate(&mut self, rate: &TickRate) {
+        let enabled = self.get_config().enabled();
+        self.stop();
+        self.set_compare(rate.compare);
+        self.modify_config(|config| {
+            config.set_mode(Mode::Cycle);
+            config.set_prescaler_enabled(rate.prescaler != 0);
+            config.set_prescaler(rate.prescaler);
+        });
+        self.reset();
+        if enabled {
+            self.start();
+        }
+    }
+}
+
+/// Both halves of the timer cascaded into a 64 bit timer
+///
+/// The configuration of the low half applies to the whole timer.
+pub struct Syst64 {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for Syst64 {}
+
+impl Syst64 {
+    #[inline(always)]
+    fn block(&self) -> &RegisterBlock {
+        unsafe { &*SYST::PTR }
+    }
+
+    /// Disables cascaded mode and releases the timer
+    #[inline]
+    pub fn free(mut self) -> SYST {
+        self.modify_config(|config| config.set_cascaded(false));
+        SYST {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads the configuration
+    #[inline]
+    pub fn get_config(&self) -> TimerConfig {
+        TimerConfig::from_bits(self.block().cfg_low.read())
+    }
+
+    /// Modifies the configuration with a single register write, cascaded mode stays enabled
+    #[inline]
+    pub fn modify_config<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut TimerConfig),
+    {
+        let mut config = self.get_config();
+        f(&mut config);
+        config.set_cascaded(true);
+        unsafe { self.block().cfg_low.write(config.bits) }
+    }
+
+    /// Returns the 64 bit counter value
+    ///
+    /// The high half is read again after the low half, until it did not change in between.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        let block = self.block();
+        loop {
+            let hi = block.cnt_high.read();
+            let lo = block.cnt_low.read();
+            if hi == block.cnt_high.read() {
+                return ((hi as u64) << 32) | lo as u64;
+            }
+        }
+    }
+
+    /// Sets the 64 bit counter value
+    #[inline]
+    pub fn set_counter(&mut self, value: u64) {
+        unsafe {
+            self.block().cnt_low.write(0);
+            self.block().cnt_high.write((value >> 32) as u32);
+            self.block().cnt_low.write(value as u32);
+        }
+    }
+
+    /// Returns the 64 bit compare value
+    #[inline]
+    pub fn get_compare(&self) -> u64 {
+        ((self.block().cmp_high.read() as u64) << 32) | self.block().cmp_low.read() as u64
+    }
+
+    /// Sets the 64 bit compare value
+    ///
+    /// The high half is parked at its maximum while the low half is written, so
+    /// the intermediate values are never reached by the counter and cannot match.
+    #[inline]
+    pub fn set_compare(&mut self, value: u64) {
+        unsafe {
+            self.block().cmp_high.write(u32::MAX);
+            self.block().cmp_low.write(value as u32);
+            self.block().cmp_high.write((value >> 32) as u32);
+        }
+    }
+
+    /// Starts counting through the start command register
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe { self.block().start_low.write(1) }
+    }
+
+    /// Resets the counter through the reset command register
+    #[inline]
+    pub fn reset(&mut self) {
+        unsafe { self.block().reset_low.write(1) }
+    }
+}
+
 impl SYST {
+    /// Enables cascaded mode and uses the timer as a single 64 bit timer
+    #[inline]
+    pub fn into_64(mut self) -> Syst64 {
+        self.enable_cascaded_mode();
+        Syst64 {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits the timer into its two halves
+    #[inline]
+    pub fn split(self) -> (TimerHalf<Lo>, TimerHalf<Hi>) {
+        (
+            TimerHalf {
+                _marker: PhantomData,
+            },
+            TimerHalf {
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    /// Joins the two halves back into the timer
+    #[inline]
+    pub fn join(_lo: TimerHalf<Lo>, _hi: TimerHalf<Hi>) -> SYST {
+        SYST {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads the configuration of the low half
+    #[inline]
+    pub fn get_config_lo() -> TimerConfig {
+        unsafe { TimerConfig::from_bits((*Self::PTR).cfg_low.read()) }
+    }
+
+    /// Writes the configuration of the low half
+    #[inline]
+    pub fn set_config_lo(&mut self, config: TimerConfig) {
+        unsafe { (*Self::PTR).cfg_low.write(config.bits) }
+    }
+
+    /// Modifies the configuration of the low half with a single register write
+    #[inline]
+    pub fn modify_config_lo<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut TimerConfig),
+    {
+        let mut config = Self::get_config_lo();
+        f(&mut config);
+        self.set_config_lo(config)
+    }
+
+    /// Reads the configuration of the high half
+    #[inline]
+    pub fn get_config_hi() -> TimerConfig {
+        unsafe { TimerConfig::from_bits((*Self::PTR).cfg_high.read()) }
+    }
+
+    /// Writes the configuration of the high half
+    #[inline]
+    pub fn set_config_hi(&mut self, config: TimerConfig) {
+        unsafe { (*Self::PTR).cfg_high.write(config.bits) }
+    }
+
+    /// Modifies the configuration of the high half with a single register write
+    #[inline]
+    pub fn modify_config_hi<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut TimerConfig),
+    {
+        let mut config = Self::get_config_hi();
+        f(&mut config);
+        self.set_config_hi(config)
+    }
+
     #[inline]
     pub fn enable_lo(&mut self) {
         unsafe { 
@@ -381,14 +1118,14 @@ impl SYST {
     #[inline]
     pub fn start_command_lo(&mut self) {
         unsafe { 
-            (*Self::PTR).cmp_low.write(1)
+            (*Self::PTR).start_low.write(1)
         }
     }
 
     #[inline]
     pub fn reset_command_lo(&mut self) {
         unsafe { 
-            (*Self::PTR).cmp_low.write(1)
+            (*Self::PTR).reset_low.write(1)
         }
     }
 
@@ -424,15 +1161,85 @@ impl SYST {
     #[inline]
     pub fn start_command_hi(&mut self) {
         unsafe { 
-            (*Self::PTR).cmp_high.write(1)
+            (*Self::PTR).start_high.write(1)
         }
     }
 
     #[inline]
     pub fn reset_command_hi(&mut self) {
         unsafe { 
-            (*Self::PTR).cmp_high.write(1)
+            (*Self::PTR).reset_high.write(1)
         }
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{best_rate, RateError, TickRate};
+
+    #[test]
+    fn best_rate_rounds_to_nearest_compare() {
+        // 32.768 cycles per tick, prescaler 2 with 11 ticks ties with prescaler 0
+        assert_eq!(
+            best_rate(32_768, 32_768, 1_000),
+            Ok(TickRate {
+                prescaler: 0,
+                compare: 33,
+                achieved_mhz: 992_970,
+                error_ppm: 7_080,
+            })
+        );
+        assert_eq!(
+            best_rate(1_000_000, 1_000_000, 1_000),
+            Ok(TickRate {
+                prescaler: 0,
+                compare: 1_000,
+                achieved_mhz: 1_000_000,
+                error_ppm: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn best_rate_error_is_negative_for_short_periods() {
+        // 3.333 cycles per tick, prescaler 2 with 1 tick ties with prescaler 0
+        assert_eq!(
+            best_rate(10_000, 10_000, 3_000),
+            Ok(TickRate {
+                prescaler: 0,
+                compare: 3,
+                achieved_mhz: 3_333_333,
+                error_ppm: -100_000,
+            })
+        );
+    }
+
+    #[test]
+    fn best_rate_uses_prescaler_beyond_compare_range() {
+        // 60 s at 100 MHz is 6e9 cycles
+        let rate = best_rate(100_000_000, 100_000_000 * 60_000_000_000, 1_000_000_000);
+        assert_eq!(
+            rate,
+            Ok(TickRate {
+                prescaler: 1,
+                compare: 3_000_000_000,
+                achieved_mhz: 17,
+                error_ppm: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn best_rate_errors() {
+        assert_eq!(best_rate(0, 1, 1), Err(RateError::Zero));
+        assert_eq!(best_rate(1_000, 1_000, 0), Err(RateError::Zero));
+        assert_eq!(best_rate(1_000, 0, 1), Err(RateError::Zero));
+        assert_eq!(best_rate(1_000, 1_000, 2_000), Err(RateError::TooFast));
+        // 2000 s at 1 GHz exceeds 256 * u32::MAX cycles
+        assert_eq!(
+            best_rate(1_000_000_000, 1_000_000_000 * 2_000_000_000_000, 1_000_000_000),
+            Err(RateError::TooSlow)
+        );
+    }
+}
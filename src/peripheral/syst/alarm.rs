@@ -0,0 +1,233 @@
+//! Software alarms multiplexed on one SYST compare register
+//!
+//! An [`AlarmQueue`] keeps up to `N` pending `(deadline, callback)` entries. The
+//! compare register of its timer half always holds the earliest deadline, and the
+//! handler of the timer interrupt calls [`AlarmQueue::on_interrupt`] to run every
+//! expired callback and re-arm the compare register.
+//!
+//! ``` ignore
+//! static ALARMS: AlarmQueue<Lo, SystInterrupt, 8> = AlarmQueue::new(SystInterrupt::LO);
+//!
+//! ALARMS.init(lo);
+//! ALARMS.schedule_after(1_000, blink).ok();
+//!
+//! fn timer_lo_handler() {
+//!     ALARMS.on_interrupt();
+//! }
+//! ```
+//!
+//! Deadlines are absolute values of the free-running 32 bit counter and are
+//! compared with wrapping arithmetic, so they must be less than 2^31 ticks ahead.
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::syst::{Half, Mode, TimerHalf};
+use crate::peripheral::CLIC;
+
+/// Callback of an alarm
+pub type Callback = fn();
+
+#[derive(Clone, Copy)]
+struct Alarm {
+    deadline: u32,
+    callback: Callback,
+}
+
+struct Inner<H, const N: usize> {
+    half: Option<TimerHalf<H>>,
+    alarms: [Option<Alarm>; N],
+}
+
+/// Queue of up to `N` alarms on the timer half `H`, whose compare event raises the interrupt `I`
+pub struct AlarmQueue<H, I, const N: usize> {
+    interrupt: I,
+    inner: Mutex<RefCell<Inner<H, N>>>,
+}
+
+/// Returns `true` if `deadline` is not in the future of `now`
+#[inline]
+fn expired(deadline: u32, now: u32) -> bool {
+    deadline.wrapping_sub(now) as i32 <= 0
+}
+
+impl<H, I, const N: usize> AlarmQueue<H, I, N> {
+    /// Creates an empty queue, its timer half is set with [`AlarmQueue::init`]
+    #[inline]
+    pub const fn new(interrupt: I) -> Self {
+        AlarmQueue {
+            interrupt,
+            inner: Mutex::new(RefCell::new(Inner {
+                half: None,
+                alarms: [None; N],
+            })),
+        }
+    }
+}
+
+impl<H, I, const N: usize> AlarmQueue<H, I, N>
+where
+    H: Half,
+    I: InterruptNumber,
+{
+    /// Starts `half` as a free-running counter
+    ///
+    /// The compare interrupt is only enabled while an alarm is pending. The CLIC line
+    /// of the interrupt has to be configured and unmasked separately.
+    pub fn init(&self, mut half: TimerHalf<H>) {
+        half.stop();
+        half.reset();
+        half.modify_config(|config| {
+            config.set_mode(Mode::Continuous);
+            config.set_one_shot(false);
+            config.set_interrupt(false);
+        });
+        half.start();
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow(cs).borrow_mut();
+            inner.half = Some(half);
+            self.rearm(&mut inner);
+        })
+    }
+
+    /// Stops the timer half and releases it, pending alarms are dropped
+    pub fn free(&self) -> Option<TimerHalf<H>> {
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow(cs).borrow_mut();
+            inner.alarms = [None; N];
+            let mut half = inner.half.take();
+            if let Some(half) = half.as_mut() {
+                half.modify_config(|config| config.set_interrupt(false));
+                half.stop();
+            }
+            half
+        })
+    }
+
+    /// Returns the counter value of the timer half
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue was not initialized.
+    #[inline]
+    pub fn now(&self) -> u32 {
+        critical_section::with(|cs| {
+            let inner = self.inner.borrow(cs).borrow();
+            inner.half.as_ref().expect("alarm queue not initialized").get_counter()
+        })
+    }
+
+    /// Schedules `callback` to run from the timer interrupt once the counter reaches `deadline`
+    ///
+    /// Returns `Err(callback)` if all `N` entries are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue was not initialized.
+    pub fn schedule(&self, deadline: u32, callback: Callback) -> Result<(), Callback> {
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow(cs).borrow_mut();
+            assert!(inner.half.is_some(), "alarm queue not initialized");
+            match inner.alarms.iter_mut().find(|slot| slot.is_none()) {
+                Some(slot) => *slot = Some(Alarm { deadline, callback }),
+                None => return Err(callback),
+            }
+            self.rearm(&mut inner);
+            Ok(())
+        })
+    }
+
+    /// Schedules `callback` to run `ticks` after now
+    ///
+    /// Returns `Err(callback)` if all `N` entries are used.
+    #[inline]
+    pub fn schedule_after(&self, ticks: u32, callback: Callback) -> Result<(), Callback> {
+        critical_section::with(|_| self.schedule(self.now().wrapping_add(ticks), callback))
+    }
+
+    /// Removes every pending entry of `callback`, returns how many were removed
+    pub fn cancel(&self, callback: Callback) -> usize {
+        critical_section::with(|cs| {
+            let mut inner = self.inner.borrow(cs).borrow_mut();
+            let mut removed = 0;
+            for slot in inner.alarms.iter_mut() {
+                if slot.map(|alarm| alarm.callback as usize) == Some(callback as usize) {
+                    *slot = None;
+                    removed += 1;
+                }
+            }
+            self.rearm(&mut inner);
+            removed
+        })
+    }
+
+    /// Runs every expired callback and re-arms the compare register, to be called from the timer interrupt
+    ///
+    /// Callbacks run outside of the critical section and may schedule new alarms.
+    pub fn on_interrupt(&self) {
+        // NOTE clearing the event before scanning the queue keeps any later compare match pending
+        CLIC::unpend(self.interrupt);
+        loop {
+            let mut expired_callbacks: [Option<Callback>; N] = [None; N];
+            let any = critical_section::with(|cs| {
+                let mut inner = self.inner.borrow(cs).borrow_mut();
+                let now = match inner.half.as_ref() {
+                    Some(half) => half.get_counter(),
+                    None => return false,
+                };
+                let mut any = false;
+                for (slot, callback) in inner.alarms.iter_mut().zip(expired_callbacks.iter_mut()) {
+                    if let Some(alarm) = *slot {
+                        if expired(alarm.deadline, now) {
+                            *callback = Some(alarm.callback);
+                            *slot = None;
+                            any = true;
+                        }
+                    }
+                }
+                self.rearm(&mut inner);
+                any
+            });
+            if !any {
+                break;
+            }
+            for callback in expired_callbacks.iter().flatten() {
+                callback();
+            }
+        }
+    }
+
+    /// Programs the earliest deadline, and pends the interrupt if it already passed
+    ///
+    /// The compare interrupt is disabled while no alarm is pending, as the counter
+    /// would otherwise match the last deadline again on every wrap.
+    fn rearm(&self, inner: &mut Inner<H, N>) {
+        let half = match inner.half.as_mut() {
+            Some(half) => half,
+            None => return,
+        };
+        let now = half.get_counter();
+        let earliest = inner
+            .alarms
+            .iter()
+            .flatten()
+            .min_by_key(|alarm| alarm.deadline.wrapping_sub(now) as i32);
+        let enabled = half.get_config().interrupt();
+        match earliest {
+            Some(alarm) => {
+                half.set_compare(alarm.deadline);
+                if !enabled {
+                    half.modify_config(|config| config.set_interrupt(true));
+                }
+                // the compare event only fires when the counter passes the deadline
+                if expired(alarm.deadline, half.get_counter()) {
+                    CLIC::pend(self.interrupt);
+                }
+            }
+            None if enabled => half.modify_config(|config| config.set_interrupt(false)),
+            None => {}
+        }
+    }
+}
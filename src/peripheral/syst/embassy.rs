@@ -0,0 +1,156 @@
+//! `embassy-time` driver on the cascaded SYST timer
+//!
+//! [`SystDriver`] uses both halves as a free-running 64 bit counter for `now()` and
+//! the compare registers for wake-ups. The clock source and prescaler of the low half
+//! have to be set to the tick rate selected with the `tick-hz-*` feature of
+//! `embassy-time-driver`, and the handler of the compare interrupt has to call
+//! [`SystDriver::on_interrupt`].
+//!
+//! ``` ignore
+//! embassy_time_driver::time_driver_impl!(
+//!     static DRIVER: SystDriver<SystInterrupt, 8> = SystDriver::new(SystInterrupt::LO)
+//! );
+//!
+//! DRIVER.init(p.SYST);
+//!
+//! fn timer_lo_handler() {
+//!     DRIVER.on_interrupt();
+//! }
+//! ```
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use critical_section::{CriticalSection, Mutex};
+use embassy_time_driver::Driver;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::syst::{Mode, Syst64};
+use crate::peripheral::{CLIC, SYST};
+
+struct Inner<const N: usize> {
+    timer: Option<Syst64>,
+    queue: [Option<(u64, Waker)>; N],
+}
+
+/// `embassy-time` driver with up to `N` pending wake-ups, whose compare event raises the interrupt `I`
+pub struct SystDriver<I, const N: usize> {
+    interrupt: I,
+    inner: Mutex<RefCell<Inner<N>>>,
+}
+
+impl<I, const N: usize> SystDriver<I, N> {
+    const EMPTY: Option<(u64, Waker)> = None;
+
+    /// Creates the driver, the timer is started with [`SystDriver::init`]
+    #[inline]
+    pub const fn new(interrupt: I) -> Self {
+        SystDriver {
+            interrupt,
+            inner: Mutex::new(RefCell::new(Inner {
+                timer: None,
+                queue: [Self::EMPTY; N],
+            })),
+        }
+    }
+}
+
+impl<I, const N: usize> SystDriver<I, N>
+where
+    I: InterruptNumber,
+{
+    /// Starts `syst` as a free-running 64 bit counter with its compare interrupt enabled
+    ///
+    /// The clock source and prescaler of the low half are kept. The CLIC line of the
+    /// interrupt has to be configured and unmasked separately.
+    pub fn init(&self, syst: SYST) {
+        let mut timer = syst.into_64();
+        timer.modify_config(|config| {
+            config.set_enabled(false);
+            config.set_mode(Mode::Continuous);
+            config.set_one_shot(false);
+            config.set_interrupt(true);
+        });
+        timer.reset();
+        timer.set_compare(u64::MAX);
+        timer.start();
+        critical_section::with(|cs| self.inner.borrow(cs).borrow_mut().timer = Some(timer));
+    }
+
+    /// Wakes every expired task and re-arms the compare register, to be called from the timer interrupt
+    pub fn on_interrupt(&self) {
+        // NOTE clearing the event before scanning the queue keeps any later compare match pending
+        CLIC::unpend(self.interrupt);
+        critical_section::with(|cs| self.rearm(cs));
+    }
+
+    /// Wakes every expired task and programs the earliest pending wake-up
+    fn rearm(&self, cs: CriticalSection) {
+        let mut inner = self.inner.borrow(cs).borrow_mut();
+        let inner = &mut *inner;
+        let timer = match inner.timer.as_mut() {
+            Some(timer) => timer,
+            None => return,
+        };
+
+        loop {
+            let now = timer.now();
+            let mut next = u64::MAX;
+            for slot in inner.queue.iter_mut() {
+                match slot {
+                    Some((at, _)) if *at <= now => {
+                        if let Some((_, waker)) = slot.take() {
+                            waker.wake();
+                        }
+                    }
+                    Some((at, _)) => next = next.min(*at),
+                    None => {}
+                }
+            }
+
+            timer.set_compare(next);
+            // the compare event only fires when the counter passes the wake-up time
+            if next > timer.now() {
+                break;
+            }
+        }
+    }
+}
+
+impl<I, const N: usize> Driver for SystDriver<I, N>
+where
+    I: InterruptNumber + Send + Sync + 'static,
+{
+    fn now(&self) -> u64 {
+        critical_section::with(|cs| {
+            let inner = self.inner.borrow(cs).borrow();
+            inner.timer.as_ref().map_or(0, |timer| timer.now())
+        })
+    }
+
+    /// Schedules a wake-up of `waker` at `at`
+    ///
+    /// A task already waiting keeps its earlier wake-up. If all `N` entries are used
+    /// the task is woken right away and polls again.
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        critical_section::with(|cs| {
+            {
+                let mut inner = self.inner.borrow(cs).borrow_mut();
+                let queue = &mut inner.queue;
+                if let Some((time, _)) = queue
+                    .iter_mut()
+                    .flatten()
+                    .find(|(_, queued)| queued.will_wake(waker))
+                {
+                    *time = (*time).min(at);
+                } else if let Some(slot) = queue.iter_mut().find(|slot| slot.is_none()) {
+                    *slot = Some((at, waker.clone()));
+                } else {
+                    waker.wake_by_ref();
+                    return;
+                }
+            }
+            self.rearm(cs);
+        })
+    }
+}
@@ -0,0 +1,106 @@
+//! Tickless idle on a SYST timer half
+//!
+//! Instead of a periodic tick, [`TicklessIdle::idle`] programs the timer half for
+//! the next scheduled wakeup in one-shot mode and stalls the hart with `wfi`. The
+//! decision to sleep is taken with interrupts disabled, so an interrupt arriving
+//! in between cannot be missed.
+//!
+//! ``` ignore
+//! let mut idle = TicklessIdle::new(lo, SystInterrupt::<PulpLines>::LO);
+//! loop {
+//!     let slept = idle.idle(|| scheduler.next_wakeup_in_ticks());
+//!     scheduler.advance(slept);
+//! }
+//! ```
+
+use crate::asm;
+use crate::interrupt::{self, InterruptNumber};
+use crate::peripheral::syst::{Half, Mode, TimerHalf};
+use crate::peripheral::CLIC;
+
+/// Tickless idle on the timer half `H`, whose compare event raises the interrupt `I`
+pub struct TicklessIdle<H, I> {
+    half: TimerHalf<H>,
+    interrupt: I,
+    slept: u64,
+}
+
+impl<H, I> TicklessIdle<H, I>
+where
+    H: Half,
+    I: InterruptNumber,
+{
+    /// Creates the idle routine on `half`
+    ///
+    /// The CLIC line `interrupt` has to be unmasked so the compare event wakes the
+    /// hart. It is unpended after each sleep, so its handler does not run for wake-ups.
+    #[inline]
+    pub fn new(mut half: TimerHalf<H>, interrupt: I) -> Self {
+        half.stop();
+        TicklessIdle {
+            half,
+            interrupt,
+            slept: 0,
+        }
+    }
+
+    /// Releases the timer half
+    #[inline]
+    pub fn free(mut self) -> TimerHalf<H> {
+        self.half.stop();
+        self.half
+    }
+
+    /// Returns the total number of ticks slept
+    #[inline]
+    pub fn slept(&self) -> u64 {
+        self.slept
+    }
+
+    /// Sleeps until the next wakeup or until another interrupt arrives
+    ///
+    /// `next_wakeup` runs with interrupts disabled and returns the ticks until the
+    /// next scheduled wakeup, or `None` if work is pending and the hart must not
+    /// sleep. Returns the number of ticks slept, pending interrupts are serviced
+    /// once this function returns.
+    pub fn idle<F>(&mut self, next_wakeup: F) -> u32
+    where
+        F: FnOnce() -> Option<u32>,
+    {
+        let slept = interrupt::free(|| {
+            let ticks = match next_wakeup() {
+                Some(ticks) if ticks > 0 => ticks,
+                _ => return 0,
+            };
+
+            self.half.stop();
+            self.half.reset();
+            self.half.set_compare(ticks);
+            self.half.modify_config(|config| {
+                config.set_mode(Mode::Continuous);
+                config.set_one_shot(true);
+                config.set_interrupt(true);
+            });
+            self.half.start();
+
+            // NOTE `wfi` also returns for interrupts masked by `mstatus.MIE`
+            unsafe { asm::wfi() };
+
+            // the half clears its enable bit once it reached the compare value
+            let slept = if self.half.get_config().enabled() {
+                self.half.get_counter().min(ticks)
+            } else {
+                ticks
+            };
+            self.half.modify_config(|config| {
+                config.set_enabled(false);
+                config.set_interrupt(false);
+            });
+            CLIC::unpend(self.interrupt);
+            slept
+        });
+
+        self.slept += slept as u64;
+        slept
+    }
+}
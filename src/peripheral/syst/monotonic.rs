@@ -0,0 +1,113 @@
+//! Monotonic timer on the cascaded SYST timer
+//!
+//! [`SystMonotonic`] free-runs both halves as a 64 bit counter and uses the compare
+//! registers for alarms. It implements the RTIC `Monotonic` trait, with time
+//! expressed as `fugit` instants at `TIMER_HZ`, the rate the timer counts at.
+//!
+//! ``` ignore
+//! // reference clock of 32768 Hz without prescaler
+//! let mono = SystMonotonic::<_, 32_768>::new(p.SYST, Interrupt::TIMER_LO);
+//! ```
+
+use fugit::{TimerDurationU64, TimerInstantU64};
+use rtic_monotonic::Monotonic;
+
+use crate::interrupt::InterruptNumber;
+use crate::peripheral::syst::{Mode, Syst64};
+use crate::peripheral::{CLIC, SYST};
+
+/// Monotonic timer counting at `TIMER_HZ`, raising the interrupt `I` on compare
+pub struct SystMonotonic<I, const TIMER_HZ: u32> {
+    timer: Syst64,
+    interrupt: I,
+}
+
+impl<I, const TIMER_HZ: u32> SystMonotonic<I, TIMER_HZ>
+where
+    I: InterruptNumber,
+{
+    /// Starts `syst` as a free-running 64 bit counter, `interrupt` is the CLIC line of its compare event
+    ///
+    /// The clock source and prescaler configured on the low half are kept and
+    /// have to result in `TIMER_HZ`.
+    pub fn new(syst: SYST, interrupt: I) -> Self {
+        let mut timer = syst.into_64();
+        timer.modify_config(|config| {
+            config.set_enabled(false);
+            config.set_mode(Mode::Continuous);
+            config.set_one_shot(false);
+            config.set_interrupt(false);
+        });
+        timer.reset();
+        timer.set_compare(u64::MAX);
+        timer.start();
+        SystMonotonic { timer, interrupt }
+    }
+
+    /// Stops the timer and releases it
+    #[inline]
+    pub fn free(mut self) -> SYST {
+        self.timer.modify_config(|config| config.set_enabled(false));
+        self.timer.free()
+    }
+
+    /// Returns the time left until `instant`, zero if it already passed
+    #[inline]
+    pub fn until(&mut self, instant: TimerInstantU64<TIMER_HZ>) -> TimerDurationU64<TIMER_HZ> {
+        instant
+            .checked_duration_since(self.now())
+            .unwrap_or_else(|| TimerDurationU64::from_ticks(0))
+    }
+}
+
+impl<I, const TIMER_HZ: u32> Monotonic for SystMonotonic<I, TIMER_HZ>
+where
+    I: InterruptNumber,
+{
+    type Instant = TimerInstantU64<TIMER_HZ>;
+    type Duration = TimerDurationU64<TIMER_HZ>;
+
+    #[inline]
+    fn now(&mut self) -> Self::Instant {
+        TimerInstantU64::from_ticks(self.timer.now())
+    }
+
+    /// Schedules the compare interrupt at `instant`
+    ///
+    /// The line is pended right away if `instant` already passed.
+    #[inline]
+    fn set_compare(&mut self, instant: Self::Instant) {
+        self.timer.set_compare(instant.ticks());
+        if instant <= self.now() {
+            CLIC::pend(self.interrupt);
+        }
+    }
+
+    #[inline]
+    fn clear_compare_flag(&mut self) {
+        CLIC::unpend(self.interrupt);
+    }
+
+    #[inline]
+    fn zero() -> Self::Instant {
+        TimerInstantU64::from_ticks(0)
+    }
+
+    /// Restarts the counter from zero
+    #[inline]
+    unsafe fn reset(&mut self) {
+        self.timer.reset();
+    }
+
+    /// Enables the compare interrupt
+    #[inline]
+    fn enable_timer(&mut self) {
+        self.timer.modify_config(|config| config.set_interrupt(true));
+    }
+
+    /// Disables the compare interrupt
+    #[inline]
+    fn disable_timer(&mut self) {
+        self.timer.modify_config(|config| config.set_interrupt(false));
+    }
+}
@@ -0,0 +1,198 @@
+//! `embedded-hal` timers on the halves of the SYST timer
+//!
+//! Durations are converted to ticks using the frequency of the selected clock
+//! source and the prescaler configured on the half, which divides the clock by
+//! its value plus one when enabled.
+
+use core::marker::PhantomData;
+use core::time::Duration;
+
+use embedded_hal::timer::{CountDown, Periodic};
+use void::Void;
+
+use crate::peripheral::clic::Trigger;
+use crate::peripheral::syst::{Half, Mode, PulpLines, SystLines, TimerConfig, TimerHalf};
+use crate::peripheral::CLIC;
+
+/// Converts `duration` into ticks of `half` counting at `clock_hz` before the prescaler
+///
+/// # Panics
+///
+/// Panics if `duration` does not fit into the 32 bit compare register.
+fn ticks<H>(half: &TimerHalf<H>, clock_hz: u32, duration: Duration) -> u32
+where
+    H: Half,
+{
+    let config = half.get_config();
+    let divider = if config.prescaler_enabled() {
+        config.prescaler() as u128 + 1
+    } else {
+        1
+    };
+    // rounded up, so the timer never runs shorter than `duration`
+    let scale = divider * 1_000_000_000;
+    let ticks = (duration.as_nanos() * clock_hz as u128 + scale - 1) / scale;
+    assert!(ticks <= u32::MAX as u128, "duration exceeds the compare register");
+    ticks.max(1) as u32
+}
+
+/// Stops `half`, loads `compare` and restarts it from zero with `f` applied to its configuration
+fn restart<H, F>(half: &mut TimerHalf<H>, compare: u32, f: F)
+where
+    H: Half,
+    F: FnOnce(&mut TimerConfig),
+{
+    half.stop();
+    half.reset();
+    half.set_compare(compare);
+    half.modify_config(|config| {
+        config.set_mode(Mode::Cycle);
+        f(config);
+    });
+    half.start();
+}
+
+/// One-shot `CountDown` timer on a half of the SYST timer
+///
+/// The half stops once the counter reaches the compare value, which clears its enable bit.
+pub struct OneShotTimer<H> {
+    half: TimerHalf<H>,
+    clock_hz: u32,
+}
+
+impl<H> OneShotTimer<H>
+where
+    H: Half,
+{
+    /// Creates a timer on `half`, whose clock source runs at `clock_hz`
+    #[inline]
+    pub fn new(half: TimerHalf<H>, clock_hz: u32) -> Self {
+        OneShotTimer { half, clock_hz }
+    }
+
+    /// Stops the timer and releases the half
+    #[inline]
+    pub fn free(mut self) -> TimerHalf<H> {
+        self.half.stop();
+        self.half
+    }
+}
+
+impl<H> CountDown for OneShotTimer<H>
+where
+    H: Half,
+{
+    type Time = Duration;
+
+    /// Starts the timer, durations are limited to `u32::MAX` ticks
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is longer than `u32::MAX` ticks.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Duration>,
+    {
+        let compare = ticks(&self.half, self.clock_hz, count.into());
+        restart(&mut self.half, compare, |config| config.set_one_shot(true));
+    }
+
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        if self.half.get_config().enabled() {
+            Err(nb::Error::WouldBlock)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Periodic `CountDown` timer on a half of the SYST timer
+///
+/// The counter restarts from zero after reaching the compare value. Each compare event
+/// is latched in the pending bit of the compare interrupt, on the CLIC lines given by
+/// `L`. The line is configured as edge triggered and kept masked, so it cannot be
+/// used by a handler at the same time.
+pub struct PeriodicTimer<H, L = PulpLines> {
+    half: TimerHalf<H>,
+    clock_hz: u32,
+    _lines: PhantomData<fn() -> L>,
+}
+
+impl<H, L> PeriodicTimer<H, L>
+where
+    H: Half,
+    L: SystLines,
+{
+    /// Creates a timer on `half`, whose clock source runs at `clock_hz`
+    ///
+    /// The CLIC line of the compare interrupt is masked and set to edge triggered.
+    #[inline]
+    pub fn new(half: TimerHalf<H>, clock_hz: u32, clic: &mut CLIC) -> Self {
+        let interrupt = half.interrupt::<L>();
+        CLIC::mask(interrupt);
+        // NOTE(unsafe) the line is masked, no handler depends on its trigger
+        unsafe { clic.set_trig(interrupt, Trigger::EdgePositive) };
+        PeriodicTimer {
+            half,
+            clock_hz,
+            _lines: PhantomData,
+        }
+    }
+
+    /// Stops the timer and releases the half
+    #[inline]
+    pub fn free(mut self) -> TimerHalf<H> {
+        self.half.stop();
+        self.half.modify_config(|config| config.set_interrupt(false));
+        CLIC::unpend(self.half.interrupt::<L>());
+        self.half
+    }
+}
+
+impl<H, L> CountDown for PeriodicTimer<H, L>
+where
+    H: Half,
+    L: SystLines,
+{
+    type Time = Duration;
+
+    /// Starts the timer, periods are limited to `u32::MAX` ticks
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is longer than `u32::MAX` ticks.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Duration>,
+    {
+        let compare = ticks(&self.half, self.clock_hz, count.into());
+        let interrupt = self.half.interrupt::<L>();
+        CLIC::mask(interrupt);
+        self.half.stop();
+        CLIC::unpend(interrupt);
+        restart(&mut self.half, compare, |config| {
+            config.set_one_shot(false);
+            config.set_interrupt(true);
+        });
+    }
+
+    /// Returns `Ok` once a period elapsed since the last `Ok`
+    ///
+    /// Several periods elapsed between two calls are reported as one.
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        let interrupt = self.half.interrupt::<L>();
+        if CLIC::is_pending(interrupt) {
+            CLIC::unpend(interrupt);
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+impl<H, L> Periodic for PeriodicTimer<H, L>
+where
+    H: Half,
+    L: SystLines,
+{
+}
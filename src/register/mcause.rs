@@ -1,5 +1,7 @@
 //! mcause register
 
+use crate::interrupt::InterruptNumber;
+
 /// mcause register
 #[derive(Clone, Copy, Debug)]
 pub struct Mcause {
@@ -25,6 +27,15 @@ pub enum Interrupt {
     UserExternal,
     SupervisorExternal,
     MachineExternal,
+    /// CLIC software interrupt (`csip`)
+    #[cfg(feature="clic")]
+    ClicSoft,
+    /// Reserved CLIC local interrupt (13-15)
+    #[cfg(feature="clic")]
+    Local(u16),
+    /// CLIC external interrupt, carrying its interrupt number (16 and up)
+    #[cfg(feature="clic")]
+    External(u16),
     Unknown,
 }
 
@@ -61,6 +72,12 @@ impl Interrupt {
             8 => Interrupt::UserExternal,
             9 => Interrupt::SupervisorExternal,
             11 => Interrupt::MachineExternal,
+            #[cfg(feature="clic")]
+            12 => Interrupt::ClicSoft,
+            #[cfg(feature="clic")]
+            13..=15 => Interrupt::Local(nr as u16),
+            #[cfg(feature="clic")]
+            16..=4095 => Interrupt::External(nr as u16),
             _ => Interrupt::Unknown,
         }
     }
@@ -97,6 +114,7 @@ impl Mcause {
 
     /// Returns the code field
     #[inline]
+    #[cfg(not(feature="clic"))]
     pub fn code(&self) -> usize {
         match () {
             #[cfg(target_pointer_width = "32")]
@@ -108,6 +126,30 @@ impl Mcause {
         }
     }
 
+    /// Returns the code field in CLIC mode
+    ///
+    /// In CLIC mode the exception code is only 12 bits wide, the upper bits hold
+    /// the previous interrupt level and the hardware vectoring state.
+    #[inline]
+    #[cfg(feature="clic")]
+    pub fn code(&self) -> usize {
+        self.bits & 0xFFF
+    }
+
+    /// Returns the interrupt level before the trap was taken (`mpil`)
+    #[inline]
+    #[cfg(feature="clic")]
+    pub fn mpil(&self) -> usize {
+        (self.bits >> 16) & 0xFF
+    }
+
+    /// Is the hart in the middle of fetching a hardware vectored handler address (`minhv`)
+    #[inline]
+    #[cfg(feature="clic")]
+    pub fn minhv(&self) -> bool {
+        self.bits & (1 << 30) != 0
+    }
+
     /// Trap Cause
     #[inline]
     pub fn cause(&self) -> Trap {
@@ -118,6 +160,19 @@ impl Mcause {
         }
     }
 
+    /// Returns the interrupt as a variant of the device interrupt enum `I`
+    ///
+    /// Returns `None` if the trap cause is an exception and `Some(Err(code))`
+    /// if `code` is not a valid interrupt number of `I`.
+    #[inline]
+    pub fn interrupt_as<I: InterruptNumber>(&self) -> Option<Result<I, usize>> {
+        if self.is_interrupt() {
+            Some(I::from_number(self.code()))
+        } else {
+            None
+        }
+    }
+
     /// Is trap cause an interrupt.
     #[inline]
     pub fn is_interrupt(&self) -> bool {